@@ -1,10 +1,14 @@
+use std::time::Instant;
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    board::{Board, BoardKind, Marble},
-    coordinate::Coordinate,
+    board::{Board, BoardKind, Marble, Ring},
+    clock::{Clock, TimeControl},
+    coordinate::{Coordinate, CoordinateIter},
     error::{self, ZertzCoreError},
-    game::{CatchableMove, Game, GameState, MarbleCount, Player},
+    game::{CatchableMove, Game, GameState, MarbleCount, Move, Player, Rules},
+    record::GameRecord,
 };
 
 #[derive(Debug, Default)]
@@ -20,42 +24,111 @@ pub struct GameOutputData {
     pub movable_list: Vec<CatchableMove>,
 }
 
+/// What the last move did to the board, so that it can be shown step by step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveChange {
+    pub mv: Move,
+    /// The board right before the move.
+    pub before: Board,
+    /// Rings cut off from the board by the move, whose marbles are caught.
+    pub isolated: Vec<Coordinate>,
+}
+
+/// Why a game is over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EndReason {
+    /// A player has caught the marbles needed to win.
+    WinCondition,
+    /// The same board came up three times.
+    Repetition,
+    /// No marble can be put any more, because every ring is gone or no
+    /// marble is left to put.
+    BoardFilled,
+    Resignation,
+    /// Both players agreed to a draw.
+    Agreement,
+    /// The flag of the player to move has fallen.
+    Timeout,
+    /// An arbiter decided the result.
+    Adjudication,
+}
+
+/// Outcome of a finished game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameResult {
+    /// `None` for a draw.
+    pub winner: Option<Player>,
+    pub reason: EndReason,
+}
+
+// Position a game was set up from, other than the empty board.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct History {
-    board: Board,
-    current_player: Player,
-    players_score: [MarbleCount; 2],
-    total_marble: MarbleCount,
+struct Start {
+    game: Game,
+    // Moves played before `game`, which cannot be taken back.
+    ply: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct App {
     game: Game,
-    game_history: Vec<History>,
-    prev_game_history: Option<History>,
+    #[serde(default)]
+    moves: Vec<Move>,
+    /// Names of Alice and Bob, which may be empty.
+    #[serde(default)]
+    players: [String; 2],
     #[serde(skip)]
     output_data: Option<GameOutputData>,
+    #[serde(default)]
+    pub(crate) clock: Option<Clock>,
+    #[serde(default)]
+    pub(crate) result: Option<GameResult>,
+    /// Player whose offer of a draw is not answered yet.
+    #[serde(default)]
+    pub(crate) draw_offer: Option<Player>,
+    /// Moves taken back, the last one first.
+    #[serde(skip)]
+    undone: Vec<Move>,
+    #[serde(skip)]
+    last_change: Option<MoveChange>,
+    /// Where moves are played again from when some are taken back, which is
+    /// the empty board if `None`.
+    #[serde(default)]
+    start: Option<Start>,
     pub players_score: [MarbleCount; 2],
     pub total_marble: MarbleCount,
 }
 
 impl App {
     pub fn new(kind: BoardKind) -> Self {
-        let game = Game::new(kind);
+        Self::with_rules(kind, Rules::default())
+    }
+
+    pub fn with_rules(kind: BoardKind, rules: Rules) -> Self {
+        let game = Game::with_rules(kind, rules);
         let players_score = game.players_score;
         let total_marble = game.total_marble;
 
         Self {
             game,
-            game_history: Vec::with_capacity(100),
-            prev_game_history: None,
+            moves: Vec::with_capacity(100),
+            players: Default::default(),
             output_data: None,
+            clock: None,
+            result: None,
+            draw_offer: None,
+            undone: Vec::new(),
+            last_change: None,
+            start: None,
             players_score,
             total_marble,
         }
     }
 
     pub fn play(&mut self, data: &Option<GameInputData>) -> error::Result<()> {
+        self.check_flag();
+
+        let mover = self.game.current_player;
         let list_all_catchable = self.game.list_all_catchable();
 
         match self.game.game_state {
@@ -85,10 +158,36 @@ impl App {
                     ..
                 }) = data
                 {
+                    let before = self.game.board;
+                    let was_vacant =
+                        matches!(self.game.board.get(*remove_coord), Some(Ring::Vacant));
                     self.game.put_marble(*put_coord, *remove_coord, *marble)?;
-                    self.game_history.push(self.get_game_history());
-                    self.prev_game_history = None;
+                    let is_removed =
+                        matches!(self.game.board.get(*remove_coord), Some(Ring::Empty));
+                    let remove_coord = (was_vacant && is_removed).then_some(*remove_coord);
+                    let mv = Move::Put {
+                        put_coord: *put_coord,
+                        remove_coord,
+                        marble: *marble,
+                    };
+                    self.moves.push(mv);
+                    // Every other ring which is gone held a marble, maybe the
+                    // one just put.
+                    let isolated = CoordinateIter::new()
+                        .filter(|&coord| {
+                            Some(coord) != remove_coord
+                                && before[coord] != Ring::Empty
+                                && self.game.board[coord] == Ring::Empty
+                        })
+                        .collect();
+                    self.last_change = Some(MoveChange {
+                        mv,
+                        before,
+                        isolated,
+                    });
+                    self.undone.clear();
                     self.output_data = None;
+                    self.press_clock();
                 } else {
                     return Err(ZertzCoreError::InvalidInputData);
                 }
@@ -99,14 +198,21 @@ impl App {
                     ..
                 }) = data
                 {
+                    let before = self.game.board;
                     self.game.catch_marble(*catch_data)?;
-                    self.game_history.push(self.get_game_history());
-                    self.prev_game_history = None;
+                    self.moves.push(Move::Catch(*catch_data));
+                    self.last_change = Some(MoveChange {
+                        mv: Move::Catch(*catch_data),
+                        before,
+                        isolated: Vec::new(),
+                    });
+                    self.undone.clear();
                     self.output_data = self
                         .game
                         .sequential_move_list
                         .clone()
                         .map(|movable_list| GameOutputData { movable_list });
+                    self.press_clock();
                 } else {
                     return Err(ZertzCoreError::InvalidInputData);
                 }
@@ -117,72 +223,114 @@ impl App {
         self.players_score = self.game.players_score;
         self.total_marble = self.game.total_marble;
 
+        // Playing instead of answering declines the offer of a draw.
+        if self.draw_offer == Some(mover.opponent()) && self.game.current_player != mover {
+            self.draw_offer = None;
+        }
+        self.check_result();
+
         Ok(())
     }
 
-    // FIXME: This has a bug!
-    pub fn rewind(&mut self) {
-        if let Some(History {
-            board,
-            current_player,
-            players_score,
-            total_marble,
-        }) = self.prev_game_history
-        {
-            self.game.board = board;
-            self.game.current_player = current_player;
-            self.game.players_score = players_score;
-            self.game.total_marble = total_marble;
-        } else if let Some(
-            game_history @ History {
-                board,
-                current_player,
-                players_score,
-                total_marble,
-            },
-        ) = self.game_history.pop()
-        {
-            self.game.board = board;
-            self.game.current_player = current_player;
-            self.game.players_score = players_score;
-            self.game.total_marble = total_marble;
-            self.prev_game_history = Some(game_history);
+    /// Play `mv` for the current player. Unlike [`App::play`], this passes
+    /// through the states which do not need any input, and rejects an illegal
+    /// move without touching the game, with an error telling why.
+    pub fn play_move(&mut self, mv: Move) -> error::Result<()> {
+        self.check_flag();
+        self.settle()?;
+
+        if !self.game.legal_moves().contains(&mv) {
+            return Err(self.game.refusal(mv));
         }
 
-        self.game.calculate_components();
-        self.game.game_state = GameState::CheckIsCatchable;
+        let data = match mv {
+            Move::Put {
+                put_coord,
+                remove_coord,
+                marble,
+            } => GameInputData {
+                put_coord: Some(put_coord),
+                remove_coord: Some(remove_coord.unwrap_or(put_coord)),
+                marble: Some(marble),
+                catch_data: None,
+            },
+            Move::Catch(catch_data) => GameInputData {
+                catch_data: Some(catch_data),
+                ..GameInputData::default()
+            },
+        };
+        self.play(&Some(data))?;
+
+        self.settle()
     }
 
-    pub fn force_rewind(&mut self) {
-        if let Some(
-            game_history @ History {
-                board,
-                current_player,
-                players_score,
-                total_marble,
-            },
-        ) = self.game_history.pop()
+    fn settle(&mut self) -> error::Result<()> {
+        while let GameState::CheckIsCatchable | GameState::FoundSequentialMove =
+            self.game.game_state
         {
-            self.game.board = board;
-            self.game.current_player = current_player;
-            self.game.players_score = players_score;
-            self.game.total_marble = total_marble;
-            self.prev_game_history = Some(game_history);
+            self.play(&None)?;
         }
 
-        self.game.calculate_components();
-        self.game.game_state = GameState::CheckIsCatchable;
+        Ok(())
     }
 
     pub fn get_output(&self) -> Option<GameOutputData> {
         self.output_data.clone()
     }
 
+    /// What the last move played did, which is `None` after a move is taken
+    /// back.
+    #[inline]
+    pub fn last_change(&self) -> Option<&MoveChange> {
+        self.last_change.as_ref()
+    }
+
     #[inline]
     pub fn get_game_state(&self) -> GameState {
         self.game.game_state
     }
 
+    #[inline]
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    #[inline]
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    #[inline]
+    pub fn players(&self) -> &[String; 2] {
+        &self.players
+    }
+
+    pub fn set_players(&mut self, players: [String; 2]) {
+        self.players = players;
+    }
+
+    pub fn to_record(&self) -> GameRecord {
+        GameRecord {
+            players: self.players.clone(),
+            moves: self.moves.clone(),
+            result: match self.game.game_state {
+                GameState::GameEnd(winner) => Some(winner),
+                _ => None,
+            },
+            reason: self.result().map(|result| result.reason),
+            ..GameRecord::new(self.game.board.kind, self.game.rules)
+        }
+    }
+
+    /// A new game on the same kind of board, between the same players with
+    /// the same rules and time control.
+    pub fn rematch(&self) -> Self {
+        let mut app = Self::with_rules(self.game.board.kind, self.game.rules);
+        app.set_time_control(self.clock.as_ref().map(Clock::control));
+        app.set_players(self.players.clone());
+        app
+    }
+
     pub fn get_current_board(&self) -> Board {
         self.game.board
     }
@@ -208,22 +356,565 @@ impl App {
         let board_kind = game.board.kind;
         game.calculate_components();
 
+        let rules = game.rules;
+
         Ok(Self {
+            start: Some(Start {
+                game: game.clone(),
+                ply: 0,
+            }),
             game,
-            ..Self::new(board_kind)
+            ..Self::with_rules(board_kind, rules)
         })
     }
 
+    /// Continue a game from `game` after `moves`. Unlike [`App::load`], only
+    /// the moves played from `game` on can be taken back.
+    pub fn from_game(mut game: Game, moves: Vec<Move>) -> Self {
+        let board_kind = game.board.kind;
+        let rules = game.rules;
+        game.calculate_components();
+
+        Self {
+            start: Some(Start {
+                game: game.clone(),
+                ply: moves.len(),
+            }),
+            players_score: game.players_score,
+            total_marble: game.total_marble,
+            game,
+            moves,
+            ..Self::with_rules(board_kind, rules)
+        }
+    }
+
+    /// Play every move of `record` again, so the whole game can be rewound.
+    /// A game which did not end on the board, as by resignation, ends the
+    /// same way again.
+    pub fn from_record(record: &GameRecord) -> error::Result<Self> {
+        let mut app = Self::with_rules(record.board_kind, record.rules);
+        app.set_players(record.players.clone());
+        for &mv in record.moves.iter() {
+            app.play_move(mv)?;
+        }
+        if let (None, Some(winner), Some(reason)) = (app.result(), record.result, record.reason) {
+            app.finish((winner != Player::Tie).then_some(winner), reason);
+        }
+
+        Ok(app)
+    }
+
     pub fn save_without_history(&self) -> error::Result<String> {
         serde_json::to_string(&self.game).map_err(ZertzCoreError::SaveFailed)
     }
+}
+
+// ╭──────────────────────────────────────────────────────────╮
+// │                         Clock Api                        │
+// ╰──────────────────────────────────────────────────────────╯
+
+impl App {
+    /// Play with a clock, which is paused until [`App::start_clock`].
+    /// `None` plays without any time limit.
+    pub fn set_time_control(&mut self, control: Option<TimeControl>) {
+        self.clock = control.map(Clock::new);
+    }
+
+    #[inline]
+    pub fn clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
+    }
+
+    pub fn start_clock(&mut self) {
+        if let GameState::GameEnd(_) = self.game.game_state {
+            return;
+        }
+        if let Some(clock) = self.clock.as_mut() {
+            clock.start(Instant::now());
+        }
+    }
+
+    pub fn pause_clock(&mut self) {
+        if let Some(clock) = self.clock.as_mut() {
+            clock.pause(Instant::now());
+        }
+    }
+
+    /// End the game if the player to move has run out of time, and return
+    /// whether the game has ended so.
+    pub fn check_flag(&mut self) -> bool {
+        if let GameState::GameEnd(_) = self.game.game_state {
+            return self.result().map(|result| result.reason) == Some(EndReason::Timeout);
+        }
+        let Some(flagged) = self
+            .clock
+            .as_mut()
+            .and_then(|clock| clock.flag(Instant::now()))
+        else {
+            return false;
+        };
+
+        self.finish(Some(flagged.opponent()), EndReason::Timeout);
+        true
+    }
+
+    fn press_clock(&mut self) {
+        let now = Instant::now();
+        let Some(clock) = self.clock.as_mut() else {
+            return;
+        };
+
+        match self.game.game_state {
+            GameState::GameEnd(_) => clock.pause(now),
+            _ => clock.press(self.game.current_player, now),
+        }
+    }
+}
+
+// ╭──────────────────────────────────────────────────────────╮
+// │                       Takeback Api                       │
+// ╰──────────────────────────────────────────────────────────╯
+
+impl App {
+    /// Take back the last move, which [`App::redo`] can play again. A
+    /// capture sequence is taken back one jump at a time.
+    pub fn undo(&mut self) -> error::Result<()> {
+        // Only a game ended by the rules can be taken back.
+        if let Some(GameResult {
+            reason:
+                EndReason::Resignation
+                | EndReason::Agreement
+                | EndReason::Timeout
+                | EndReason::Adjudication,
+            ..
+        }) = self.result
+        {
+            return Err(ZertzCoreError::GameIsOver);
+        }
+
+        self.force_rewind()
+    }
+
+    /// Take back the last move like [`App::undo`], even after the game was
+    /// resigned, drawn by agreement, lost on time or adjudicated.
+    pub fn force_rewind(&mut self) -> error::Result<()> {
+        let mut moves = self.moves.clone();
+        let mv = moves.pop().ok_or(ZertzCoreError::NothingToUndo)?;
+        self.replay(&moves)?;
+        self.undone.push(mv);
+
+        Ok(())
+    }
+
+    /// Play the last move taken back again.
+    pub fn redo(&mut self) -> error::Result<()> {
+        let mut undone = std::mem::take(&mut self.undone);
+        let mv = undone.pop().ok_or(ZertzCoreError::NothingToRedo)?;
+        let played = self.play_move(mv);
+        if played.is_err() {
+            undone.push(mv);
+        }
+        self.undone = undone;
+
+        played
+    }
+
+    #[inline]
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    // Set the game up again from its start through `moves`. The clock goes
+    // on for the player to move, without giving back the time spent.
+    fn replay(&mut self, moves: &[Move]) -> error::Result<()> {
+        let (mut app, ply) = match &self.start {
+            Some(Start { game, ply }) if moves.len() >= *ply => {
+                (Self::from_game(game.clone(), moves[..*ply].to_vec()), *ply)
+            }
+            Some(_) => return Err(ZertzCoreError::NothingToUndo),
+            None => (Self::with_rules(self.game.board.kind, self.game.rules), 0),
+        };
+        for &mv in moves[ply..].iter() {
+            app.play_move(mv)?;
+        }
+
+        self.game = app.game;
+        self.moves = app.moves;
+        self.output_data = None;
+        self.last_change = None;
+        self.result = app.result;
+        self.draw_offer = None;
+        self.players_score = app.players_score;
+        self.total_marble = app.total_marble;
+        if let Some(clock) = self.clock.as_mut() {
+            clock.hand_over(self.game.current_player, Instant::now());
+        }
+
+        Ok(())
+    }
+}
+
+// ╭──────────────────────────────────────────────────────────╮
+// │                      Game Result Api                     │
+// ╰──────────────────────────────────────────────────────────╯
+
+impl App {
+    /// Result of the game, or `None` if it is not over.
+    pub fn result(&self) -> Option<GameResult> {
+        let GameState::GameEnd(winner) = self.game.game_state else {
+            return None;
+        };
+
+        // Saves from before results were recorded only know the winner.
+        self.result.or(Some(GameResult {
+            winner: (winner != Player::Tie).then_some(winner),
+            reason: if winner == Player::Tie {
+                EndReason::Repetition
+            } else {
+                EndReason::WinCondition
+            },
+        }))
+    }
+
+    #[inline]
+    pub fn draw_offer(&self) -> Option<Player> {
+        self.draw_offer
+    }
+
+    pub fn resign(&mut self, player: Player) -> error::Result<()> {
+        self.ensure_playing(player)?;
+        self.finish(Some(player.opponent()), EndReason::Resignation);
+        Ok(())
+    }
+
+    /// Offer a draw, which stands until the opponent answers it or plays.
+    pub fn offer_draw(&mut self, player: Player) -> error::Result<()> {
+        self.ensure_playing(player)?;
+        self.draw_offer = Some(player);
+        Ok(())
+    }
 
-    fn get_game_history(&self) -> History {
-        History {
-            board: self.game.board,
-            current_player: self.game.current_player,
-            players_score: self.game.players_score,
-            total_marble: self.game.total_marble,
+    pub fn accept_draw(&mut self, player: Player) -> error::Result<()> {
+        self.ensure_playing(player)?;
+        if self.draw_offer != Some(player.opponent()) {
+            return Err(ZertzCoreError::NoDrawOffer);
         }
+        self.finish(None, EndReason::Agreement);
+        Ok(())
+    }
+
+    pub fn decline_draw(&mut self, player: Player) -> error::Result<()> {
+        self.ensure_playing(player)?;
+        if self.draw_offer != Some(player.opponent()) {
+            return Err(ZertzCoreError::NoDrawOffer);
+        }
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// End the game with the result decided by an arbiter. `None` is a draw.
+    pub fn adjudicate(&mut self, winner: Option<Player>) -> error::Result<()> {
+        if let GameState::GameEnd(_) = self.game.game_state {
+            return Err(ZertzCoreError::GameIsOver);
+        }
+        self.finish(winner, EndReason::Adjudication);
+        Ok(())
+    }
+
+    fn ensure_playing(&self, player: Player) -> error::Result<()> {
+        match self.game.game_state {
+            GameState::GameEnd(_) => Err(ZertzCoreError::GameIsOver),
+            _ if player == Player::Tie => Err(ZertzCoreError::InvalidInputData),
+            _ => Ok(()),
+        }
+    }
+
+    // Record how the rules have ended the game, or end it when nobody can
+    // move any more.
+    fn check_result(&mut self) {
+        match self.game.game_state {
+            GameState::GameEnd(_) if self.result.is_none() => self.result = self.result(),
+            GameState::PutMarble if self.game.legal_moves().is_empty() => {
+                self.finish(None, EndReason::BoardFilled)
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(&mut self, winner: Option<Player>, reason: EndReason) {
+        self.game.game_state = GameState::GameEnd(winner.unwrap_or(Player::Tie));
+        self.result = Some(GameResult { winner, reason });
+        self.draw_offer = None;
+        self.output_data = None;
+        self.pause_clock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn resignation_and_draw_offers() {
+        let mut app = App::new(BoardKind::Rings37);
+        assert!(app.accept_draw(Player::Bob).is_err());
+
+        app.offer_draw(Player::Alice).unwrap();
+        assert_eq!(app.draw_offer(), Some(Player::Alice));
+        // Alice cannot answer her own offer.
+        assert!(app.accept_draw(Player::Alice).is_err());
+        app.decline_draw(Player::Bob).unwrap();
+        assert_eq!(app.draw_offer(), None);
+
+        // Playing a move instead of answering declines the offer.
+        app.offer_draw(Player::Alice).unwrap();
+        let mv = app.game().legal_moves()[0];
+        app.play_move(mv).unwrap();
+        let mv = app.game().legal_moves()[0];
+        app.play_move(mv).unwrap();
+        assert_eq!(app.draw_offer(), None);
+
+        app.resign(Player::Alice).unwrap();
+        assert_eq!(
+            app.result(),
+            Some(GameResult {
+                winner: Some(Player::Bob),
+                reason: EndReason::Resignation,
+            })
+        );
+        assert!(app.offer_draw(Player::Bob).is_err());
+    }
+
+    #[test]
+    fn result_is_saved() {
+        let mut app = App::new(BoardKind::Rings37);
+        app.offer_draw(Player::Bob).unwrap();
+        app.accept_draw(Player::Alice).unwrap();
+
+        let app = App::load(app.save().unwrap()).unwrap();
+        assert!(matches!(
+            app.get_game_state(),
+            GameState::GameEnd(Player::Tie)
+        ));
+        assert_eq!(
+            app.result(),
+            Some(GameResult {
+                winner: None,
+                reason: EndReason::Agreement,
+            })
+        );
+        assert_eq!(app.to_record().reason, Some(EndReason::Agreement));
+
+        let rematch = app.rematch();
+        assert_eq!(rematch.result(), None);
+        assert_eq!(rematch.get_current_board().kind, BoardKind::Rings37);
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut app = App::new(BoardKind::Rings37);
+        assert!(app.undo().is_err());
+
+        let mut boards = vec![app.get_current_board()];
+        while app.moves().len() < 40 && app.result().is_none() {
+            let mv = app.game().legal_moves()[0];
+            app.play_move(mv).unwrap();
+            boards.push(app.get_current_board());
+        }
+        assert!(app.moves().iter().any(|mv| matches!(mv, Move::Catch(_))));
+        let moves = app.moves().to_vec();
+
+        // Every position comes back, even in the middle of a capture sequence.
+        for board in boards.iter().rev().skip(1) {
+            app.undo().unwrap();
+            assert!(app.get_current_board().is_identical(board));
+        }
+        assert!(app.undo().is_err());
+
+        for board in boards.iter().skip(1) {
+            app.redo().unwrap();
+            assert!(app.get_current_board().is_identical(board));
+        }
+        assert_eq!(app.moves(), moves);
+        assert!(app.redo().is_err());
+
+        // A new move forgets the moves taken back.
+        app.undo().unwrap();
+        app.undo().unwrap();
+        let mv = app.game().legal_moves()[0];
+        app.play_move(mv).unwrap();
+        assert!(!app.can_redo());
+    }
+
+    #[test]
+    fn force_rewind_keeps_the_record_in_step() {
+        let mut app = App::new(BoardKind::Rings37);
+        for _ in 0..2 {
+            let mv = app.game().legal_moves()[0];
+            app.play_move(mv).unwrap();
+        }
+        let first_move = app.moves()[0];
+        app.resign(Player::Bob).unwrap();
+        assert!(app.undo().is_err());
+
+        app.force_rewind().unwrap();
+        assert_eq!(app.moves(), [first_move]);
+        assert_eq!(app.to_record().moves, [first_move]);
+        assert!(app.last_change().is_none());
+        assert!(app.result().is_none());
+
+        let mut expected = App::new(BoardKind::Rings37);
+        expected.play_move(first_move).unwrap();
+        assert_eq!(app.game().position_hash(), expected.game().position_hash());
+    }
+
+    #[test]
+    fn undo_does_not_press_the_clock() {
+        let mut app = App::new(BoardKind::Rings37);
+        app.set_time_control(Some("60+2".parse().unwrap()));
+        app.start_clock();
+        let mv = app.game().legal_moves()[0];
+        app.play_move(mv).unwrap();
+
+        app.undo().unwrap();
+        let clock = app.clock().unwrap();
+        let now = Instant::now();
+        assert_eq!(clock.running(), Player::Alice);
+        // Alice keeps the increment of her move, and Bob gets none.
+        let alice = clock.time_left(Player::Alice, now);
+        assert!(alice > Duration::from_secs(61) && alice <= Duration::from_secs(62));
+        let bob = clock.time_left(Player::Bob, now);
+        assert!(bob > Duration::from_secs(59) && bob <= Duration::from_secs(60));
+    }
+
+    #[test]
+    fn refused_move_leaves_the_game_alone() {
+        let mut app = App::new(BoardKind::Rings37);
+        let board = app.get_current_board();
+        let removable = app.get_removable_rings();
+        let inner = CoordinateIter::new()
+            .filter(|coord| board[*coord] == Ring::Vacant && !removable.contains(coord))
+            .collect::<Vec<_>>();
+
+        let mv = Move::Put {
+            put_coord: inner[0],
+            remove_coord: Some(inner[1]),
+            marble: Marble::White,
+        };
+        assert!(matches!(
+            app.play_move(mv),
+            Err(ZertzCoreError::InvalidRingToRemove)
+        ));
+
+        assert!(app.get_current_board().is_identical(&board));
+        assert_eq!(app.total_marble.get(Marble::White), 6);
+        assert_eq!(app.game().total_marble().get(Marble::White), 6);
+        assert!(app.moves().is_empty());
+        assert_eq!(app.game().current_player(), Player::Alice);
+    }
+
+    #[test]
+    fn undo_back_to_the_start_of_a_game_in_progress() {
+        let mut app = App::new(BoardKind::Rings37);
+        for _ in 0..3 {
+            let mv = app.game().legal_moves()[0];
+            app.play_move(mv).unwrap();
+        }
+        let game = app.game().clone();
+        let position_hash = game.position_hash();
+
+        for mut resumed in [
+            App::from_game(game.clone(), app.moves().to_vec()),
+            App::from_game(game.clone(), Vec::new()),
+            App::load_without_history(app.save_without_history().unwrap()).unwrap(),
+        ] {
+            let ply = resumed.moves().len();
+            let mv = resumed.game().legal_moves()[0];
+            resumed.play_move(mv).unwrap();
+
+            resumed.undo().unwrap();
+            assert_eq!(resumed.game().position_hash(), position_hash);
+            assert_eq!(resumed.moves().len(), ply);
+            assert!(matches!(resumed.undo(), Err(ZertzCoreError::NothingToUndo)));
+            resumed.redo().unwrap();
+            assert_eq!(resumed.moves(), [&app.moves()[..ply], &[mv]].concat());
+        }
+    }
+
+    #[test]
+    fn resume_from_record() {
+        let mut app = App::with_rules(BoardKind::Rings40, Rules::Blitz);
+        app.set_players(["alice".to_string(), "bob".to_string()]);
+        for _ in 0..4 {
+            let mv = app.game().legal_moves()[0];
+            app.play_move(mv).unwrap();
+        }
+
+        let resumed = App::from_record(&app.to_record()).unwrap();
+        assert_eq!(resumed.players(), app.players());
+        assert_eq!(resumed.moves(), app.moves());
+        assert!(resumed
+            .get_current_board()
+            .is_identical(&app.get_current_board()));
+        assert_eq!(resumed.rematch().players(), app.players());
+    }
+
+    #[test]
+    fn replay_a_resigned_record() {
+        let mut app = App::new(BoardKind::Rings37);
+        for _ in 0..3 {
+            let mv = app.game().legal_moves()[0];
+            app.play_move(mv).unwrap();
+        }
+        app.resign(Player::Bob).unwrap();
+        let record = app.to_record();
+        assert_eq!(record.reason, Some(EndReason::Resignation));
+
+        let replayed = App::from_record(&record).unwrap();
+        assert_eq!(replayed.result(), app.result());
+        assert_eq!(replayed.to_record().result, Some(Player::Alice));
+        let replayed = App::from_record(&App::load(app.save().unwrap()).unwrap().to_record());
+        assert_eq!(replayed.unwrap().result(), app.result());
+    }
+
+    #[test]
+    fn last_change_of_each_move() {
+        let mut app = App::new(BoardKind::Rings37);
+        assert!(app.last_change().is_none());
+
+        while app.result().is_none() {
+            let mover = app.game().current_player();
+            let caught = |app: &App| {
+                let score = app.players_score[usize::from(mover)];
+                [Marble::White, Marble::Gray, Marble::Black]
+                    .map(|marble| score.get(marble))
+                    .iter()
+                    .sum::<usize>()
+            };
+            let (board, score, pool) = (app.get_current_board(), caught(&app), app.total_marble);
+            let mv = app.game().legal_moves()[0];
+            app.play_move(mv).unwrap();
+
+            let change = app.last_change().unwrap();
+            assert_eq!(change.mv, mv);
+            assert!(change.before.is_identical(&board));
+            for &coord in &change.isolated {
+                assert_eq!(app.get_current_board()[coord], Ring::Empty);
+            }
+            // Once the pool is empty, a player puts one of their own marbles.
+            let expected = match mv {
+                Move::Put { marble, .. } if pool.get(marble) == 0 => {
+                    score + change.isolated.len() - 1
+                }
+                Move::Put { .. } => score + change.isolated.len(),
+                Move::Catch(_) => score + 1,
+            };
+            assert_eq!(caught(&app), expected);
+        }
+
+        app.undo().unwrap();
+        assert!(app.last_change().is_none());
     }
 }
@@ -40,7 +40,7 @@ impl Debug for Ring {
 }
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Marble {
     White,
     Gray,
@@ -85,6 +85,21 @@ impl Board {
         Self { kind, data }
     }
 
+    /// Unlike `==`, which only compares the shape of the boards, this also
+    /// compares the marbles on the rings.
+    pub fn is_identical(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self
+                .data
+                .iter()
+                .zip(other.data.iter())
+                .all(|rings| match rings {
+                    (Ring::Empty, Ring::Empty) | (Ring::Vacant, Ring::Vacant) => true,
+                    (Ring::Occupied(lhs), Ring::Occupied(rhs)) => lhs == rhs,
+                    _ => false,
+                })
+    }
+
     pub(crate) fn get(&self, coord: Coordinate) -> Option<&Ring> {
         if coord.x >= 9 || coord.y >= 9 {
             return None;
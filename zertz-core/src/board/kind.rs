@@ -1,3 +1,6 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
 use crate::error::ZertzCoreError;
@@ -30,6 +33,25 @@ impl TryFrom<u8> for BoardKind {
     }
 }
 
+impl Display for BoardKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", *self as u8)
+    }
+}
+
+/// Accepts both `61` and `Rings61`.
+impl FromStr for BoardKind {
+    type Err = ZertzCoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.trim_start_matches("Rings").trim_start_matches("rings");
+        let size = digits
+            .parse::<u8>()
+            .map_err(|_| ZertzCoreError::InvalidInputData)?;
+        Self::try_from(size)
+    }
+}
+
 // ╭──────────────────────────────────────────────────────────╮
 // │                   Inlined board style                    │
 // │                                                          │
@@ -0,0 +1,267 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{self, ZertzCoreError},
+    game::{Game, Move, Player},
+    record::GameRecord,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookMove {
+    pub mv: Move,
+    pub weight: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BookSelection {
+    /// Pick one of the moves with the largest weight.
+    Best,
+    /// Pick a move with the probability proportional to its weight.
+    #[default]
+    Weighted,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BuildOptions {
+    /// Only the first `max_ply` moves of each record are put in the book.
+    pub max_ply: usize,
+    /// Moves played in fewer games than this are left out.
+    pub min_games: u32,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        Self {
+            max_ply: 12,
+            min_games: 2,
+        }
+    }
+}
+
+/// Moves for the first few positions of a game, keyed by
+/// [`Game::position_hash`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpeningBook {
+    entries: BTreeMap<u64, Vec<BookMove>>,
+}
+
+impl OpeningBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(json_str: impl AsRef<str>) -> error::Result<Self> {
+        serde_json::from_str(json_str.as_ref()).map_err(ZertzCoreError::LoadFailed)
+    }
+
+    pub fn save(&self) -> error::Result<String> {
+        serde_json::to_string(self).map_err(ZertzCoreError::SaveFailed)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn moves(&self, position_hash: u64) -> &[BookMove] {
+        self.entries
+            .get(&position_hash)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Add `weight` to `mv` of the given position.
+    pub fn insert(&mut self, position_hash: u64, mv: Move, weight: u32) {
+        let book_moves = self.entries.entry(position_hash).or_default();
+
+        match book_moves.iter_mut().find(|book_move| book_move.mv == mv) {
+            Some(book_move) => book_move.weight += weight,
+            None => book_moves.push(BookMove { mv, weight }),
+        }
+    }
+
+    /// Pick a book move of `game`. Moves which are not legal in `game`, which
+    /// can only come from a hash collision, are never picked.
+    pub fn pick_move<R>(&self, game: &Game, rng: &mut R, selection: BookSelection) -> Option<Move>
+    where
+        R: Rng + ?Sized,
+    {
+        let legal_moves = game.legal_moves();
+        let mut candidates = self
+            .moves(game.position_hash())
+            .iter()
+            .filter(|book_move| book_move.weight > 0 && legal_moves.contains(&book_move.mv))
+            .collect::<Vec<_>>();
+
+        if let BookSelection::Best = selection {
+            let best_weight = candidates.iter().map(|book_move| book_move.weight).max()?;
+            candidates.retain(|book_move| book_move.weight == best_weight);
+            return Some(candidates[rng.gen_range(0..candidates.len())].mv);
+        }
+
+        let total_weight = candidates
+            .iter()
+            .map(|book_move| book_move.weight as u64)
+            .sum::<u64>();
+        if total_weight == 0 {
+            return None;
+        }
+
+        let mut pick = rng.gen_range(0..total_weight);
+        for book_move in candidates {
+            if pick < book_move.weight as u64 {
+                return Some(book_move.mv);
+            }
+            pick -= book_move.weight as u64;
+        }
+
+        unreachable!()
+    }
+
+    /// Build a book from every `*.json` game record in `dir`.
+    ///
+    /// Each time a move is played, it earns two points if the player who
+    /// played it won the game, and one point if the game was tied or not
+    /// finished. Hence the weight of a move reflects both how often it was
+    /// played and how well it did.
+    pub fn build(dir: impl AsRef<Path>, options: BuildOptions) -> error::Result<Self> {
+        let mut records = Vec::new();
+
+        for dir_entry in fs::read_dir(dir)? {
+            let path = dir_entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                records.push(GameRecord::load(fs::read_to_string(path)?)?);
+            }
+        }
+
+        Self::from_records(&records, options)
+    }
+
+    pub fn from_records(records: &[GameRecord], options: BuildOptions) -> error::Result<Self> {
+        // Moves are put in the book in a fixed order, so that a seeded pick
+        // does not change from one build to the next.
+        let mut counts: BTreeMap<(u64, Move), (u32, u32)> = BTreeMap::new();
+
+        for record in records {
+            let truncated = GameRecord {
+                moves: record.moves.iter().copied().take(options.max_ply).collect(),
+                ..record.clone()
+            };
+
+            truncated.for_each_position(|game, mv| {
+                let points = match record.result {
+                    Some(winner) if winner == game.current_player() => 2,
+                    Some(Player::Tie) | None => 1,
+                    Some(_) => 0,
+                };
+                let (games, weight) = counts.entry((game.position_hash(), mv)).or_default();
+                *games += 1;
+                *weight += points;
+            })?;
+        }
+
+        let mut output = Self::new();
+        for ((position_hash, mv), (games, weight)) in counts {
+            if games >= options.min_games && weight > 0 {
+                output.insert(position_hash, mv, weight);
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{board::BoardKind, board::Marble, coordinate::Coordinate, game::Rules};
+
+    fn put(x: usize, y: usize, marble: Marble) -> Move {
+        Move::Put {
+            put_coord: Coordinate::new(x, y),
+            remove_coord: Some(Coordinate::new(0, 0)),
+            marble,
+        }
+    }
+
+    fn record(first_move: Move, result: Player) -> GameRecord {
+        GameRecord {
+            moves: vec![first_move],
+            result: Some(result),
+            ..GameRecord::new(BoardKind::Rings37, Rules::Standard)
+        }
+    }
+
+    #[test]
+    fn weights_follow_frequency_and_result() {
+        let winning = put(3, 3, Marble::White);
+        let losing = put(2, 2, Marble::Black);
+        let records = [
+            record(winning, Player::Alice),
+            record(winning, Player::Alice),
+            record(losing, Player::Bob),
+            record(losing, Player::Bob),
+        ];
+        let options = BuildOptions::default();
+        let book = OpeningBook::from_records(&records, options).unwrap();
+
+        let game = Game::new(BoardKind::Rings37);
+        assert_eq!(
+            book.moves(game.position_hash()),
+            &[BookMove {
+                mv: winning,
+                weight: 4
+            }]
+        );
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for selection in [BookSelection::Best, BookSelection::Weighted] {
+            assert_eq!(book.pick_move(&game, &mut rng, selection), Some(winning));
+        }
+    }
+
+    #[test]
+    fn building_twice_gives_the_same_order() {
+        let records = (0..6)
+            .map(|x| {
+                let first_move = put(x, 3, Marble::White);
+                let mut game = Game::new(BoardKind::Rings37);
+                game.play_move(first_move).unwrap();
+                GameRecord {
+                    moves: vec![first_move, game.legal_moves()[0]],
+                    ..record(first_move, Player::Tie)
+                }
+            })
+            .flat_map(|record| [record.clone(), record])
+            .collect::<Vec<_>>();
+        let options = BuildOptions::default();
+        let first = OpeningBook::from_records(&records, options).unwrap();
+        let second = OpeningBook::from_records(&records, options).unwrap();
+
+        assert_eq!(first.len(), 7);
+        assert_eq!(first.save().unwrap(), second.save().unwrap());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let mut book = OpeningBook::new();
+        book.insert(1, put(3, 3, Marble::Gray), 5);
+        book.insert(1, put(3, 3, Marble::Gray), 2);
+
+        let book = OpeningBook::load(book.save().unwrap()).unwrap();
+        assert_eq!(book.moves(1)[0].weight, 7);
+        assert!(book.moves(2).is_empty());
+    }
+}
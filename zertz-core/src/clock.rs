@@ -0,0 +1,394 @@
+//! Clocks of both players.
+//!
+//! A [`Clock`] never reads the time by itself. Every method which depends on
+//! the time takes `now`, so the owner decides when a move starts and ends.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::ZertzCoreError, game::Player};
+
+/// How much time each player has.
+///
+/// As a string, times are in seconds: `300` is five minutes of sudden death,
+/// `300+5` adds five seconds after every move and `600/30x5` is ten minutes
+/// followed by five byo-yomi periods of thirty seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeControl {
+    SuddenDeath {
+        base: Duration,
+    },
+    /// `increment` is added after every move.
+    Fischer {
+        base: Duration,
+        increment: Duration,
+    },
+    /// After `base` runs out, every move must be made within `period`. A
+    /// move which takes longer uses up a period, and the flag falls when no
+    /// period is left.
+    ByoYomi {
+        base: Duration,
+        period: Duration,
+        periods: u32,
+    },
+}
+
+impl TimeControl {
+    fn base(self) -> Duration {
+        match self {
+            Self::SuddenDeath { base }
+            | Self::Fischer { base, .. }
+            | Self::ByoYomi { base, .. } => base,
+        }
+    }
+}
+
+impl Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SuddenDeath { base } => write!(f, "{}", base.as_secs()),
+            Self::Fischer { base, increment } => {
+                write!(f, "{}+{}", base.as_secs(), increment.as_secs())
+            }
+            Self::ByoYomi {
+                base,
+                period,
+                periods,
+            } => write!(f, "{}/{}x{}", base.as_secs(), period.as_secs(), periods),
+        }
+    }
+}
+
+impl FromStr for TimeControl {
+    type Err = ZertzCoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ZertzCoreError::InvalidTimeControl(s.to_string());
+        let seconds = |value: &str| {
+            value
+                .parse()
+                .map(Duration::from_secs)
+                .map_err(|_| invalid())
+        };
+
+        let control = if let Some((base, increment)) = s.split_once('+') {
+            Self::Fischer {
+                base: seconds(base)?,
+                increment: seconds(increment)?,
+            }
+        } else if let Some((base, byo_yomi)) = s.split_once('/') {
+            let (period, periods) = byo_yomi.split_once('x').ok_or_else(invalid)?;
+            Self::ByoYomi {
+                base: seconds(base)?,
+                period: seconds(period)?,
+                periods: periods.parse().map_err(|_| invalid())?,
+            }
+        } else {
+            Self::SuddenDeath { base: seconds(s)? }
+        };
+
+        let is_valid = match control {
+            Self::ByoYomi {
+                period, periods, ..
+            } => !period.is_zero() && periods > 0,
+            _ => !control.base().is_zero(),
+        };
+        is_valid.then_some(control).ok_or_else(invalid)
+    }
+}
+
+/// Time of one player between two moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerClock {
+    pub main_time: Duration,
+    /// Byo-yomi periods left, which is always 0 for other time controls.
+    pub periods: u32,
+}
+
+impl PlayerClock {
+    fn new(control: TimeControl) -> Self {
+        Self {
+            main_time: control.base(),
+            periods: match control {
+                TimeControl::ByoYomi { periods, .. } => periods,
+                _ => 0,
+            },
+        }
+    }
+
+    // The clock after thinking for `elapsed`, and the time left before the
+    // flag falls. This is `None` if it has already fallen.
+    fn spend(self, control: TimeControl, elapsed: Duration) -> Option<(Self, Duration)> {
+        if elapsed < self.main_time {
+            let main_time = self.main_time - elapsed;
+            return Some((Self { main_time, ..self }, main_time));
+        }
+
+        let TimeControl::ByoYomi { period, .. } = control else {
+            return None;
+        };
+        let mut overtime = elapsed - self.main_time;
+        let mut periods = self.periods;
+        while periods > 0 && overtime >= period {
+            overtime -= period;
+            periods -= 1;
+        }
+
+        (periods > 0).then(|| {
+            let clock = Self {
+                main_time: Duration::ZERO,
+                periods,
+            };
+            (clock, period - overtime)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Clock {
+    control: TimeControl,
+    players: [PlayerClock; 2],
+    /// Player whose time is spent, even while the clock is paused.
+    running: Player,
+    /// Time spent on the current move before the clock was last paused.
+    spent: Duration,
+    // A clock is always paused after being loaded.
+    #[serde(skip)]
+    started: Option<Instant>,
+    flagged: Option<Player>,
+}
+
+impl Clock {
+    /// A paused clock where Alice is to move.
+    pub fn new(control: TimeControl) -> Self {
+        Self {
+            control,
+            players: [PlayerClock::new(control); 2],
+            running: Player::Alice,
+            spent: Duration::ZERO,
+            started: None,
+            flagged: None,
+        }
+    }
+
+    #[inline]
+    pub fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    /// Player whose time is spent now.
+    #[inline]
+    pub fn running(&self) -> Player {
+        self.running
+    }
+
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    pub fn start(&mut self, now: Instant) {
+        if self.started.is_none() && self.flagged.is_none() {
+            self.started = Some(now);
+        }
+    }
+
+    pub fn pause(&mut self, now: Instant) {
+        self.spent = self.elapsed(now);
+        self.started = None;
+    }
+
+    /// Time left before the flag of `player` falls.
+    pub fn time_left(&self, player: Player, now: Instant) -> Duration {
+        self.current(player, now)
+            .map_or(Duration::ZERO, |(_, time_left)| time_left)
+    }
+
+    /// Byo-yomi periods left to `player`.
+    pub fn periods_left(&self, player: Player, now: Instant) -> u32 {
+        self.current(player, now)
+            .map_or(0, |(clock, _)| clock.periods)
+    }
+
+    /// End the move of the running player and start the time of `next`.
+    /// Nothing happens if `next` is already running, as in the middle of a
+    /// capture sequence.
+    pub fn press(&mut self, next: Player, now: Instant) {
+        if next == self.running || next == Player::Tie || self.flagged.is_some() {
+            return;
+        }
+
+        let side = usize::from(self.running);
+        match self.players[side].spend(self.control, self.elapsed(now)) {
+            Some((mut clock, _)) => {
+                if let TimeControl::Fischer { increment, .. } = self.control {
+                    clock.main_time += increment;
+                }
+                self.players[side] = clock;
+            }
+            None => {
+                self.flagged = Some(self.running);
+                self.started = None;
+                return;
+            }
+        }
+
+        self.running = next;
+        self.spent = Duration::ZERO;
+        if self.started.is_some() {
+            self.started = Some(now);
+        }
+    }
+
+    /// Start the time of `next` without ending a move, as when one is taken
+    /// back. Nobody is charged for the current move or given an increment.
+    pub fn hand_over(&mut self, next: Player, now: Instant) {
+        if next == self.running || next == Player::Tie || self.flagged.is_some() {
+            return;
+        }
+
+        self.running = next;
+        self.spent = Duration::ZERO;
+        if self.started.is_some() {
+            self.started = Some(now);
+        }
+    }
+
+    /// Player whose flag has fallen, if any.
+    pub fn flag(&mut self, now: Instant) -> Option<Player> {
+        if self.flagged.is_none() && self.current(self.running, now).is_none() {
+            self.flagged = Some(self.running);
+            self.started = None;
+        }
+
+        self.flagged
+    }
+
+    fn elapsed(&self, now: Instant) -> Duration {
+        self.spent
+            + self.started.map_or(Duration::ZERO, |started| {
+                now.saturating_duration_since(started)
+            })
+    }
+
+    fn current(&self, player: Player, now: Instant) -> Option<(PlayerClock, Duration)> {
+        if self.flagged == Some(player) || player == Player::Tie {
+            return None;
+        }
+
+        let clock = self.players[usize::from(player)];
+        if player == self.running {
+            clock.spend(self.control, self.elapsed(now))
+        } else {
+            clock.spend(self.control, Duration::ZERO)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        app::{App, EndReason},
+        board::BoardKind,
+        game::GameState,
+    };
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn parse_time_controls() {
+        for text in ["300", "300+5", "600/30x5"] {
+            assert_eq!(text.parse::<TimeControl>().unwrap().to_string(), text);
+        }
+        assert_eq!(
+            "60+2".parse::<TimeControl>().unwrap(),
+            TimeControl::Fischer {
+                base: secs(60),
+                increment: secs(2)
+            }
+        );
+        for text in ["", "0", "5m", "300+", "600/30", "600/0x5", "600/30x0"] {
+            assert!(text.parse::<TimeControl>().is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn fischer_increment() {
+        let start = Instant::now();
+        let mut clock = Clock::new("60+2".parse().unwrap());
+        clock.start(start);
+
+        clock.press(Player::Bob, start + secs(10));
+        assert_eq!(clock.time_left(Player::Alice, start + secs(10)), secs(52));
+        assert_eq!(clock.time_left(Player::Bob, start + secs(15)), secs(55));
+
+        // The same player going on does not press the clock.
+        clock.press(Player::Bob, start + secs(20));
+        assert_eq!(clock.time_left(Player::Bob, start + secs(20)), secs(50));
+
+        assert_eq!(clock.flag(start + secs(69)), None);
+        assert_eq!(clock.flag(start + secs(70)), Some(Player::Bob));
+    }
+
+    #[test]
+    fn byo_yomi_periods() {
+        let start = Instant::now();
+        let mut clock = Clock::new("10/5x2".parse().unwrap());
+        clock.start(start);
+
+        // 10 seconds of main time and one whole period, so one period is left.
+        clock.press(Player::Bob, start + secs(17));
+        assert_eq!(clock.periods_left(Player::Alice, start + secs(17)), 1);
+        assert_eq!(clock.time_left(Player::Alice, start + secs(17)), secs(5));
+
+        clock.press(Player::Alice, start + secs(17));
+        // A move within the period keeps it.
+        clock.press(Player::Bob, start + secs(21));
+        assert_eq!(clock.periods_left(Player::Alice, start + secs(21)), 1);
+
+        clock.press(Player::Alice, start + secs(21));
+        assert_eq!(clock.flag(start + secs(25)), None);
+        assert_eq!(clock.flag(start + secs(26)), Some(Player::Alice));
+    }
+
+    #[test]
+    fn paused_clock_keeps_the_time() {
+        let start = Instant::now();
+        let mut clock = Clock::new("60".parse().unwrap());
+        clock.start(start);
+        clock.pause(start + secs(20));
+
+        let mut clock =
+            serde_json::from_str::<Clock>(&serde_json::to_string(&clock).unwrap()).unwrap();
+        assert!(!clock.is_running());
+        let later = start + secs(1000);
+        assert_eq!(clock.time_left(Player::Alice, later), secs(40));
+
+        clock.start(later);
+        assert_eq!(clock.time_left(Player::Alice, later + secs(10)), secs(30));
+    }
+
+    #[test]
+    fn flag_fall_ends_the_game() {
+        let mut app = App::new(BoardKind::Rings37);
+        app.set_time_control(Some(TimeControl::SuddenDeath {
+            base: Duration::from_millis(1),
+        }));
+        app.start_clock();
+        std::thread::sleep(Duration::from_millis(5));
+
+        let mv = app.game().legal_moves()[0];
+        assert!(app.play_move(mv).is_err());
+        assert!(matches!(
+            app.get_game_state(),
+            GameState::GameEnd(Player::Bob)
+        ));
+        assert_eq!(app.result().unwrap().reason, EndReason::Timeout);
+    }
+}
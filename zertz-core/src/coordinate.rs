@@ -78,11 +78,17 @@ impl From<Coordinate> for usize {
 
 impl PartialOrd for Coordinate {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(if self.y == other.y {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Coordinate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.y == other.y {
             self.x.cmp(&other.x)
         } else {
             self.y.cmp(&other.y)
-        })
+        }
     }
 }
 
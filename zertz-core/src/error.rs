@@ -6,18 +6,36 @@ pub enum ZertzCoreError {
     IOErr(#[from] std::io::Error),
     #[error("Invalid board size was given. Only [37, 40, 43, 44, 48, 61] are possible. got = {0}")]
     InvalidBoardSize(u8),
-    #[error("That ring which you selected cannot be removed.")]
+    #[error("Invalid rules were given. Only [standard, blitz] are possible. got = {0}")]
+    InvalidRules(String),
+    #[error("That ring cannot be removed. Only a vacant ring on the edge of the board, which can slide out without moving the others, can be removed.")]
     InvalidRingToRemove,
-    #[error("That ring which you selected cannot take some new marble.")]
+    #[error("A marble cannot be put there. The ring must be vacant, and a marble of that color must be left in the pool, or among the marbles you caught if the pool has none of it.")]
     InvalidPuttingMarble,
     #[error("Failed to catch a marble. This is almost an internal bug.")]
     FailedToCatchMarble,
-    #[error("invalid input data was given")]
+    #[error("Invalid time control was given. Use like 300, 300+5 or 600/30x5. got = {0}")]
+    InvalidTimeControl(String),
+    #[error("The game is already over.")]
+    GameIsOver,
+    #[error("There is no offer of a draw to answer.")]
+    NoDrawOffer,
+    #[error("There is no move to take back.")]
+    NothingToUndo,
+    #[error("There is no move taken back to play again.")]
+    NothingToRedo,
+    #[error("Invalid input data was given.")]
     InvalidInputData,
+    #[error("That move cannot be played in this position.")]
+    IllegalMove,
+    #[error("cannot read the notation. got = {0}")]
+    InvalidNotation(String),
     #[error("cannot load the game data from a json file. Detail: {0}")]
     LoadFailed(serde_json::error::Error),
     #[error("cannot save the game data into a json file. Detail: {0}")]
     SaveFailed(serde_json::error::Error),
+    #[error("the solver ran out of its node budget. budget = {0}")]
+    NodeBudgetExceeded(u64),
 }
 
 pub type Result<T> = std::result::Result<T, ZertzCoreError>;
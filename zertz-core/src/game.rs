@@ -1,5 +1,6 @@
 use std::cell::Cell;
 use std::fmt::{self, Debug, Display};
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +12,7 @@ use crate::{
     coordinate::{Coordinate, CoordinateIter, Direction},
     error::{self, ZertzCoreError},
     union_find::UnionFind,
+    zobrist,
 };
 
 const MAIN_EMPTY_COORD: Coordinate = Coordinate::new(8, 0);
@@ -23,7 +25,25 @@ pub struct MarbleCount {
 }
 
 impl MarbleCount {
-    fn inc(&mut self, marble: Marble) {
+    #[inline]
+    pub const fn new(white_count: usize, gray_count: usize, black_count: usize) -> Self {
+        Self {
+            white_count,
+            gray_count,
+            black_count,
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, marble: Marble) -> usize {
+        match marble {
+            Marble::White => self.white_count,
+            Marble::Gray => self.gray_count,
+            Marble::Black => self.black_count,
+        }
+    }
+
+    pub(crate) fn inc(&mut self, marble: Marble) {
         match marble {
             Marble::White => self.white_count += 1,
             Marble::Gray => self.gray_count += 1,
@@ -57,16 +77,99 @@ impl MarbleCount {
     }
 
     #[inline]
-    fn is_win(&self) -> bool {
-        self.white_count >= 4
-            || self.gray_count >= 5
-            || self.black_count >= 6
-            || (self.white_count >= 3 && self.gray_count >= 3 && self.black_count >= 3)
+    fn is_win(&self, rules: Rules) -> bool {
+        let WinCondition {
+            white,
+            gray,
+            black,
+            each,
+        } = rules.win_condition();
+
+        self.white_count >= white
+            || self.gray_count >= gray
+            || self.black_count >= black
+            || (self.white_count >= each && self.gray_count >= each && self.black_count >= each)
+    }
+}
+
+/// A player wins by catching `white`, `gray` or `black` marbles of that
+/// color, or `each` marbles of every color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WinCondition {
+    pub white: usize,
+    pub gray: usize,
+    pub black: usize,
+    pub each: usize,
+}
+
+impl WinCondition {
+    #[inline]
+    pub fn get(&self, marble: Marble) -> usize {
+        match marble {
+            Marble::White => self.white,
+            Marble::Gray => self.gray,
+            Marble::Black => self.black,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Rules {
+    #[default]
+    Standard,
+    /// Shorter variant with fewer marbles and a lower win condition.
+    Blitz,
+}
+
+impl Rules {
+    pub fn initial_marbles(self) -> MarbleCount {
+        match self {
+            Self::Standard => MarbleCount::new(6, 8, 10),
+            Self::Blitz => MarbleCount::new(5, 7, 9),
+        }
+    }
+
+    pub fn win_condition(self) -> WinCondition {
+        match self {
+            Self::Standard => WinCondition {
+                white: 4,
+                gray: 5,
+                black: 6,
+                each: 3,
+            },
+            Self::Blitz => WinCondition {
+                white: 3,
+                gray: 4,
+                black: 5,
+                each: 2,
+            },
+        }
+    }
+}
+
+impl Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Standard => write!(f, "standard"),
+            Self::Blitz => write!(f, "blitz"),
+        }
+    }
+}
+
+impl FromStr for Rules {
+    type Err = ZertzCoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "standard" => Ok(Self::Standard),
+            "blitz" => Ok(Self::Blitz),
+            _ => Err(ZertzCoreError::InvalidRules(s.to_string())),
+        }
     }
 }
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Player {
     Alice,
     Bob,
@@ -74,6 +177,14 @@ pub enum Player {
 }
 
 impl Player {
+    pub fn opponent(self) -> Self {
+        match self {
+            Self::Alice => Self::Bob,
+            Self::Bob => Self::Alice,
+            Self::Tie => Self::Tie,
+        }
+    }
+
     fn change_player(&mut self) {
         match self {
             Self::Alice => *self = Self::Bob,
@@ -93,11 +204,30 @@ impl From<Player> for usize {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct CatchableMove {
-    start_coord: Coordinate,
-    catched_coord: Coordinate,
-    marble_land_coord: Coordinate,
+    pub(crate) start_coord: Coordinate,
+    pub(crate) catched_coord: Coordinate,
+    pub(crate) marble_land_coord: Coordinate,
+}
+
+impl CatchableMove {
+    /// Ring of the marble which jumps.
+    #[inline]
+    pub fn start_coord(&self) -> Coordinate {
+        self.start_coord
+    }
+
+    /// Ring of the marble which is caught.
+    #[inline]
+    pub fn catched_coord(&self) -> Coordinate {
+        self.catched_coord
+    }
+
+    #[inline]
+    pub fn marble_land_coord(&self) -> Coordinate {
+        self.marble_land_coord
+    }
 }
 
 impl Display for CatchableMove {
@@ -106,6 +236,20 @@ impl Display for CatchableMove {
     }
 }
 
+/// A complete action of one player, which is the unit of a game-tree search.
+///
+/// `remove_coord` of [`Move::Put`] is `None` only when there is no ring which
+/// can be removed after putting the marble.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Move {
+    Put {
+        put_coord: Coordinate,
+        remove_coord: Option<Coordinate>,
+        marble: Marble,
+    },
+    Catch(CatchableMove),
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum GameState {
     CheckIsCatchable,
@@ -115,11 +259,13 @@ pub enum GameState {
     GameEnd(Player),
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Game {
     pub(crate) board: Board,
+    #[serde(default)]
+    pub(crate) rules: Rules,
     pub(crate) board_replace_history: Vec<Board>,
-    #[serde(skip)]
+    #[serde(skip, default = "new_components")]
     pub(crate) components: UnionFind<Coordinate>,
     pub(crate) current_player: Player,
     pub(crate) game_state: GameState,
@@ -129,25 +275,30 @@ pub struct Game {
     pub(crate) sequential_move_list: Option<Vec<CatchableMove>>,
 }
 
+fn new_components() -> UnionFind<Coordinate> {
+    UnionFind::from(CoordinateIter::new().collect::<Vec<_>>())
+}
+
 // ╭──────────────────────────────────────────────────────────╮
 // │                      Basic Game Api                      │
 // ╰──────────────────────────────────────────────────────────╯
 
 impl Game {
-    pub(crate) fn new(kind: BoardKind) -> Self {
+    pub fn new(kind: BoardKind) -> Self {
+        Self::with_rules(kind, Rules::default())
+    }
+
+    pub fn with_rules(kind: BoardKind, rules: Rules) -> Self {
         let mut output = Self {
             board: Board::new(kind),
+            rules,
             board_replace_history: Vec::with_capacity(20),
-            components: UnionFind::from(CoordinateIter::new().collect::<Vec<_>>()),
+            components: new_components(),
             current_player: Player::Alice,
             game_state: GameState::PutMarble,
             players_score: [MarbleCount::default(); 2],
             repeat_count: Cell::new(0),
-            total_marble: MarbleCount {
-                white_count: 6,
-                gray_count: 8,
-                black_count: 10,
-            },
+            total_marble: rules.initial_marbles(),
             sequential_move_list: None,
         };
         output.calculate_components();
@@ -185,25 +336,22 @@ impl Game {
         self.board[start_coord] = Ring::Vacant;
         self.board[catched_coord] = Ring::Vacant;
 
+        // The repetition must be checked before the board is recorded,
+        // otherwise every capture counts as a repetition of itself.
+        let winner = self.who_is_win(&self.board);
         self.board_replace_history.push(self.board);
 
         let list_catchable = self.list_catchable_once(marble_land_coord);
         if list_catchable.is_empty() {
             self.current_player.change_player();
-            self.game_state = if let Some(winner) = self.who_is_win(&self.board) {
-                GameState::GameEnd(winner)
-            } else {
-                GameState::FoundSequentialMove
-            };
             self.sequential_move_list = None;
         } else {
-            self.game_state = if let Some(winner) = self.who_is_win(&self.board) {
-                GameState::GameEnd(winner)
-            } else {
-                GameState::FoundSequentialMove
-            };
             self.sequential_move_list = Some(list_catchable);
         }
+        self.game_state = match winner {
+            Some(winner) => GameState::GameEnd(winner),
+            None => GameState::FoundSequentialMove,
+        };
 
         Ok(())
     }
@@ -259,7 +407,11 @@ impl Game {
         self.remove_ring(remove_coord)?;
         self.remove_isolated_island();
         self.current_player.change_player();
-        self.game_state = GameState::CheckIsCatchable;
+        // Catching isolated marbles can also make a player win.
+        self.game_state = match self.winner_by_score() {
+            Some(winner) => GameState::GameEnd(winner),
+            None => GameState::CheckIsCatchable,
+        };
 
         Ok(())
     }
@@ -290,8 +442,10 @@ impl Game {
         let mut output = Vec::with_capacity(81);
 
         for coord in CoordinateIter::new() {
-            if self.valid_to_remove_ring(coord) {
-                output.push(coord);
+            if let Ring::Vacant = self.board[coord] {
+                if self.valid_to_remove_ring(coord) {
+                    output.push(coord);
+                }
             }
         }
 
@@ -398,17 +552,207 @@ impl Game {
     }
 }
 
+// ╭──────────────────────────────────────────────────────────╮
+// │                        Search Api                        │
+// ╰──────────────────────────────────────────────────────────╯
+
+impl Game {
+    #[inline]
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    #[inline]
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    #[inline]
+    pub fn players_score(&self) -> [MarbleCount; 2] {
+        self.players_score
+    }
+
+    #[inline]
+    pub fn total_marble(&self) -> MarbleCount {
+        self.total_marble
+    }
+
+    #[inline]
+    pub fn current_player(&self) -> Player {
+        self.current_player
+    }
+
+    #[inline]
+    pub fn game_state(&self) -> GameState {
+        self.game_state
+    }
+
+    /// Zobrist hash of the current position. Two games have the same hash if
+    /// the board, the marble pool, both scores, the player to move and the
+    /// pending capture sequence agree, regardless of the move order.
+    pub fn position_hash(&self) -> u64 {
+        zobrist::hash(self)
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        match (self.game_state, &self.sequential_move_list) {
+            (GameState::GameEnd(_), _) => Vec::new(),
+            (GameState::CheckIsCatchable, _) | (GameState::CatchMarble, None) => {
+                let list_all_catchable = self.list_all_catchable();
+                if list_all_catchable.is_empty() {
+                    self.list_put_moves()
+                } else {
+                    list_all_catchable.into_iter().map(Move::Catch).collect()
+                }
+            }
+            (
+                GameState::FoundSequentialMove | GameState::CatchMarble,
+                Some(sequential_move_list),
+            ) => sequential_move_list
+                .iter()
+                .copied()
+                .map(Move::Catch)
+                .collect(),
+            (GameState::FoundSequentialMove | GameState::PutMarble, _) => self.list_put_moves(),
+        }
+    }
+
+    /// The error telling why `mv`, which is not one of the legal moves, is
+    /// refused.
+    pub(crate) fn refusal(&self, mv: Move) -> ZertzCoreError {
+        let Move::Put {
+            put_coord,
+            remove_coord,
+            marble,
+        } = mv
+        else {
+            return ZertzCoreError::IllegalMove;
+        };
+
+        let player_score = self.players_score[usize::from(self.current_player)];
+        let is_vacant = matches!(self.board.get(put_coord), Some(Ring::Vacant));
+        let is_left = self.total_marble.get(marble) > 0 || player_score.get(marble) > 0;
+        let is_removable = remove_coord.is_none_or(|remove_coord| {
+            remove_coord != put_coord && self.collect_removable_rings().contains(&remove_coord)
+        });
+
+        if !is_vacant || !is_left {
+            ZertzCoreError::InvalidPuttingMarble
+        } else if !is_removable {
+            ZertzCoreError::InvalidRingToRemove
+        } else {
+            ZertzCoreError::IllegalMove
+        }
+    }
+
+    /// Play a move without keeping any history. This is meant for searchers
+    /// which clone the game for each node, so the game is left in an
+    /// unspecified state if an error is returned.
+    pub fn play_move(&mut self, mv: Move) -> error::Result<()> {
+        self.settle_game_state();
+
+        match (self.game_state, mv) {
+            (
+                GameState::PutMarble,
+                Move::Put {
+                    put_coord,
+                    remove_coord,
+                    marble,
+                },
+            ) => self.put_marble(put_coord, remove_coord.unwrap_or(put_coord), marble)?,
+            (GameState::CatchMarble, Move::Catch(catch_data)) => {
+                if !self.legal_moves().contains(&mv) {
+                    return Err(ZertzCoreError::IllegalMove);
+                }
+                self.catch_marble(catch_data)?;
+            }
+            _ => return Err(ZertzCoreError::IllegalMove),
+        }
+
+        self.settle_game_state();
+
+        Ok(())
+    }
+
+    fn list_put_moves(&self) -> Vec<Move> {
+        let player_score = self.players_score[usize::from(self.current_player)];
+        let vacant_rings = CoordinateIter::new()
+            .filter(|coord| matches!(self.board[*coord], Ring::Vacant))
+            .collect::<Vec<_>>();
+        let removable_rings = self.collect_removable_rings();
+
+        let mut output = Vec::with_capacity(3 * vacant_rings.len() * removable_rings.len());
+
+        for marble in [Marble::White, Marble::Gray, Marble::Black] {
+            if self.total_marble.get(marble) == 0 && player_score.get(marble) == 0 {
+                continue;
+            }
+
+            for &put_coord in vacant_rings.iter() {
+                let prev_len = output.len();
+                for &remove_coord in removable_rings.iter() {
+                    if remove_coord != put_coord {
+                        output.push(Move::Put {
+                            put_coord,
+                            remove_coord: Some(remove_coord),
+                            marble,
+                        });
+                    }
+                }
+                if output.len() == prev_len {
+                    output.push(Move::Put {
+                        put_coord,
+                        remove_coord: None,
+                        marble,
+                    });
+                }
+            }
+        }
+
+        output
+    }
+
+    // Resolve the states which `App::play` passes through without any input.
+    fn settle_game_state(&mut self) {
+        loop {
+            self.game_state = match self.game_state {
+                GameState::CheckIsCatchable if self.list_all_catchable().is_empty() => {
+                    GameState::PutMarble
+                }
+                GameState::CheckIsCatchable => GameState::CatchMarble,
+                GameState::FoundSequentialMove if self.sequential_move_list.is_some() => {
+                    GameState::CatchMarble
+                }
+                GameState::FoundSequentialMove => GameState::PutMarble,
+                _ => break,
+            };
+        }
+    }
+}
+
 // ╭──────────────────────────────────────────────────────────╮
 // │                     Check who is win                     │
 // ╰──────────────────────────────────────────────────────────╯
 
 impl Game {
+    pub(crate) fn winner_by_score(&self) -> Option<Player> {
+        if self.players_score[0].is_win(self.rules) {
+            Some(Player::Alice)
+        } else if self.players_score[1].is_win(self.rules) {
+            Some(Player::Bob)
+        } else {
+            None
+        }
+    }
+
     fn who_is_win(&self, board: &Board) -> Option<Player> {
-        if self.players_score[0].is_win() {
-            return Some(Player::Alice);
-        } else if self.players_score[1].is_win() {
-            return Some(Player::Bob);
-        } else if self.board_replace_history.contains(board) {
+        if let Some(winner) = self.winner_by_score() {
+            return Some(winner);
+        } else if self
+            .board_replace_history
+            .iter()
+            .any(|prev_board| prev_board.is_identical(board))
+        {
             let count = self.repeat_count.get();
             self.repeat_count.set(count + 1);
 
@@ -439,3 +783,101 @@ impl Debug for Game {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::endgame_position;
+
+    #[test]
+    fn only_vacant_rings_are_removable() {
+        let game = endgame_position(&[
+            ((0, 0), Ring::Vacant),
+            ((1, 0), Ring::Occupied(Marble::White)),
+        ]);
+
+        assert_eq!(game.collect_removable_rings(), [Coordinate::new(0, 0)]);
+    }
+
+    #[test]
+    fn put_without_removal_when_no_free_ring_is_vacant() {
+        let mut game = endgame_position(&[
+            ((0, 0), Ring::Vacant),
+            ((1, 0), Ring::Occupied(Marble::White)),
+        ]);
+        let mv = Move::Put {
+            put_coord: Coordinate::new(0, 0),
+            remove_coord: None,
+            marble: Marble::Gray,
+        };
+
+        assert!(game.legal_moves().contains(&mv));
+        game.play_move(mv).unwrap();
+        assert_eq!(game.total_marble().get(Marble::Gray), 7);
+    }
+
+    #[test]
+    fn catching_isolated_marbles_can_win() {
+        let mut game = endgame_position(&[
+            ((0, 0), Ring::Vacant),
+            ((1, 0), Ring::Vacant),
+            ((2, 0), Ring::Occupied(Marble::White)),
+            ((3, 0), Ring::Vacant),
+        ]);
+        for _ in 0..3 {
+            game.players_score[0].inc(Marble::White);
+        }
+
+        game.play_move(Move::Put {
+            put_coord: Coordinate::new(3, 0),
+            remove_coord: Some(Coordinate::new(1, 0)),
+            marble: Marble::Black,
+        })
+        .unwrap();
+
+        assert_eq!(game.players_score()[0].get(Marble::White), 4);
+        assert!(matches!(
+            game.game_state(),
+            GameState::GameEnd(Player::Alice)
+        ));
+    }
+
+    fn capture_position() -> (Game, Move) {
+        let game = endgame_position(&[
+            ((0, 0), Ring::Occupied(Marble::White)),
+            ((1, 0), Ring::Occupied(Marble::Gray)),
+            ((2, 0), Ring::Vacant),
+            ((4, 4), Ring::Vacant),
+        ]);
+        game.repeat_count.set(2);
+        let mv = Move::Catch(CatchableMove {
+            start_coord: Coordinate::new(0, 0),
+            catched_coord: Coordinate::new(1, 0),
+            marble_land_coord: Coordinate::new(2, 0),
+        });
+
+        (game, mv)
+    }
+
+    #[test]
+    fn capture_is_not_a_repetition_of_itself() {
+        let (mut game, mv) = capture_position();
+
+        game.play_move(mv).unwrap();
+
+        assert!(matches!(game.game_state(), GameState::PutMarble));
+        assert_eq!(game.current_player(), Player::Bob);
+    }
+
+    #[test]
+    fn capture_back_to_a_recorded_board_is_a_repetition() {
+        let (mut game, mv) = capture_position();
+        let mut after = game.clone();
+        after.play_move(mv).unwrap();
+        game.board_replace_history.push(after.board);
+
+        game.play_move(mv).unwrap();
+
+        assert!(matches!(game.game_state(), GameState::GameEnd(Player::Tie)));
+    }
+}
@@ -1,8 +1,15 @@
 pub mod app;
 pub mod board;
+pub mod book;
+pub mod clock;
 pub mod coordinate;
 pub mod error;
 pub mod game;
+pub mod net;
+pub mod notation;
+pub mod record;
+pub mod search;
 
 mod macros;
 mod union_find;
+mod zobrist;
@@ -0,0 +1,153 @@
+//! Messages between a game server and its clients.
+//!
+//! Every message is a single line of JSON, so a stream can be read with
+//! [`BufRead::read_line`] and written by anything which can print a line.
+
+use std::io::{BufRead, Write};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    app::{App, GameResult},
+    clock::Clock,
+    error::{self, ZertzCoreError},
+    game::{Game, Move, Player},
+};
+
+pub const DEFAULT_PORT: u16 = 7734;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Take a seat. A player who was disconnected gets the same seat back by
+    /// joining with the same name.
+    Join {
+        name: String,
+    },
+    /// Follow the game without a seat. An observer gets the whole game
+    /// first and then every move, but cannot play.
+    Watch,
+    Play {
+        mv: Move,
+    },
+    Resign,
+    OfferDraw,
+    AcceptDraw,
+    DeclineDraw,
+    Leave,
+}
+
+/// The whole game as the server sees it, which is sent after every change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    pub game: Game,
+    pub moves: Vec<Move>,
+    /// Names of Alice and Bob, which are empty for a free seat.
+    pub players: [String; 2],
+    /// Clock of the server, which is paused on the way.
+    #[serde(default)]
+    pub clock: Option<Clock>,
+    #[serde(default)]
+    pub result: Option<GameResult>,
+    #[serde(default)]
+    pub draw_offer: Option<Player>,
+}
+
+impl GameSnapshot {
+    pub fn new(app: &App, players: [String; 2]) -> Self {
+        Self {
+            game: app.game().clone(),
+            moves: app.moves().to_vec(),
+            players,
+            clock: app.clock().cloned(),
+            result: app.result(),
+            draw_offer: app.draw_offer(),
+        }
+    }
+
+    pub fn into_app(self) -> App {
+        let mut app = App::from_game(self.game, self.moves);
+        app.clock = self.clock;
+        app.result = self.result;
+        app.draw_offer = self.draw_offer;
+        app
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Welcome {
+        player: Player,
+    },
+    State {
+        snapshot: Box<GameSnapshot>,
+    },
+    /// A move which was played, sent to observers instead of the state.
+    Moved {
+        mv: Move,
+    },
+    Rejected {
+        reason: String,
+    },
+}
+
+pub fn send<T: Serialize>(writer: &mut impl Write, message: &T) -> error::Result<()> {
+    let mut line = serde_json::to_string(message).map_err(ZertzCoreError::SaveFailed)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes())?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Read the next message. This returns `None` if the stream is closed.
+pub fn receive<T: DeserializeOwned>(reader: &mut impl BufRead) -> error::Result<Option<T>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(ZertzCoreError::LoadFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::board::BoardKind;
+
+    #[test]
+    fn send_and_receive() {
+        let mut app = App::new(BoardKind::Rings37);
+        let mv = app.game().legal_moves()[0];
+        app.play_move(mv).unwrap();
+
+        let mut buffer = Vec::new();
+        send(&mut buffer, &ClientMessage::Play { mv }).unwrap();
+        let snapshot = GameSnapshot::new(&app, ["alice".to_string(), String::new()]);
+        send(
+            &mut buffer,
+            &ServerMessage::State {
+                snapshot: Box::new(snapshot),
+            },
+        )
+        .unwrap();
+
+        let mut reader = Cursor::new(buffer);
+        assert_eq!(
+            receive::<ClientMessage>(&mut reader).unwrap(),
+            Some(ClientMessage::Play { mv })
+        );
+        let Some(ServerMessage::State { snapshot }) = receive(&mut reader).unwrap() else {
+            panic!("not a state");
+        };
+        let app = (*snapshot).into_app();
+        assert_eq!(app.moves(), &[mv]);
+        assert_eq!(app.game().current_player(), Player::Bob);
+        assert!(receive::<ServerMessage>(&mut reader).unwrap().is_none());
+    }
+}
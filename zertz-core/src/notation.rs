@@ -0,0 +1,333 @@
+//! Text forms of coordinates, moves and positions, which are meant for
+//! protocols and files read by people.
+//!
+//! - A coordinate is a column letter from `a` followed by a row number from
+//!   `1`, so `Coordinate::new(3, 0)` is `d1`.
+//! - Putting a marble is the marble letter (`W`, `G` or `B`), the ring where
+//!   it is put and the removed ring after a comma, like `Wd4,a1`. The removed
+//!   ring is left out if there is no ring to remove, like `Wd4`.
+//! - Catching a marble is the ring where the jumping marble starts, `x` and the
+//!   ring where it lands, like `d4xf6`.
+//! - A position is seven fields separated by spaces, like
+//!   `37 standard oooo5/ooooo4/oooooo3/ooooooo2/-oooooo2/--ooooo2/---oooo2/9/9 a 6/8/10 0/0/0 0/0/0`:
+//!   board kind, rules, rings, player to move (`a` or `b`), marbles in the
+//!   pool, marbles caught by Alice and marbles caught by Bob. Marble counts
+//!   are white/gray/black. Rings are listed from the first row, with `/`
+//!   between the rows: `o` is a vacant ring, `w`, `g` and `b` are rings with
+//!   a marble, `-` is a removed ring, and a digit is that many removed rings
+//!   at the end of a row. An eighth field is only there in the middle of a
+//!   capture sequence, and is the ring of the marble which must go on
+//!   jumping.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::{
+    board::{BoardKind, Marble, Ring},
+    coordinate::Coordinate,
+    error::{self, ZertzCoreError},
+    game::{CatchableMove, Game, GameState, MarbleCount, Move, Player, Rules},
+};
+
+const HEX_DIRECTIONS: [(isize, isize); 6] = [(1, 0), (1, 1), (0, 1), (-1, 0), (-1, -1), (0, -1)];
+
+pub fn format_coordinate(coord: Coordinate) -> String {
+    format!("{}{}", (b'a' + coord.x as u8) as char, coord.y + 1)
+}
+
+pub fn parse_coordinate(s: &str) -> error::Result<Coordinate> {
+    let invalid = || ZertzCoreError::InvalidNotation(s.to_string());
+
+    let mut chars = s.chars();
+    let x = match chars.next() {
+        Some(column @ 'a'..='i') => column as usize - 'a' as usize,
+        _ => return Err(invalid()),
+    };
+    let y = chars
+        .as_str()
+        .parse::<usize>()
+        .map_err(|_| invalid())?
+        .checked_sub(1)
+        .filter(|y| *y < 9)
+        .ok_or_else(invalid)?;
+
+    Ok(Coordinate::new(x, y))
+}
+
+fn marble_letter(marble: Marble) -> char {
+    match marble {
+        Marble::White => 'W',
+        Marble::Gray => 'G',
+        Marble::Black => 'B',
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Put {
+                put_coord,
+                remove_coord,
+                marble,
+            } => {
+                write!(
+                    f,
+                    "{}{}",
+                    marble_letter(marble),
+                    format_coordinate(put_coord)
+                )?;
+                if let Some(remove_coord) = remove_coord {
+                    write!(f, ",{}", format_coordinate(remove_coord))?;
+                }
+                Ok(())
+            }
+            Self::Catch(catch_data) => write!(
+                f,
+                "{}x{}",
+                format_coordinate(catch_data.start_coord),
+                format_coordinate(catch_data.marble_land_coord)
+            ),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = ZertzCoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ZertzCoreError::InvalidNotation(s.to_string());
+
+        if let Some((start, land)) = s.split_once('x') {
+            let start_coord = parse_coordinate(start)?;
+            let marble_land_coord = parse_coordinate(land)?;
+            let (dx, dy) = (
+                marble_land_coord.x as isize - start_coord.x as isize,
+                marble_land_coord.y as isize - start_coord.y as isize,
+            );
+            if dx % 2 != 0 || dy % 2 != 0 || !HEX_DIRECTIONS.contains(&(dx / 2, dy / 2)) {
+                return Err(invalid());
+            }
+
+            return Ok(Self::Catch(CatchableMove {
+                start_coord,
+                catched_coord: Coordinate::new(
+                    (start_coord.x + marble_land_coord.x) / 2,
+                    (start_coord.y + marble_land_coord.y) / 2,
+                ),
+                marble_land_coord,
+            }));
+        }
+
+        let marble = match s.chars().next() {
+            Some('W') => Marble::White,
+            Some('G') => Marble::Gray,
+            Some('B') => Marble::Black,
+            _ => return Err(invalid()),
+        };
+        let (put, remove) = match s[1..].split_once(',') {
+            Some((put, remove)) => (put, Some(remove)),
+            None => (&s[1..], None),
+        };
+
+        Ok(Self::Put {
+            put_coord: parse_coordinate(put)?,
+            remove_coord: remove.map(parse_coordinate).transpose()?,
+            marble,
+        })
+    }
+}
+
+fn format_marble_count(count: MarbleCount) -> String {
+    format!(
+        "{}/{}/{}",
+        count.get(Marble::White),
+        count.get(Marble::Gray),
+        count.get(Marble::Black)
+    )
+}
+
+fn parse_marble_count(s: &str) -> error::Result<MarbleCount> {
+    let counts = s
+        .split('/')
+        .map(|count| count.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ZertzCoreError::InvalidNotation(s.to_string()))?;
+
+    match counts[..] {
+        [white, gray, black] => Ok(MarbleCount::new(white, gray, black)),
+        _ => Err(ZertzCoreError::InvalidNotation(s.to_string())),
+    }
+}
+
+impl Game {
+    /// Position string of the game, which is described in the
+    /// [module documentation](self).
+    pub fn to_position_string(&self) -> String {
+        let rows = (0..9)
+            .map(|y| {
+                let row = (0..9)
+                    .map(|x| match self.board[Coordinate::new(x, y)] {
+                        Ring::Empty => '-',
+                        Ring::Vacant => 'o',
+                        Ring::Occupied(Marble::White) => 'w',
+                        Ring::Occupied(Marble::Gray) => 'g',
+                        Ring::Occupied(Marble::Black) => 'b',
+                    })
+                    .collect::<String>();
+                let trimmed = row.trim_end_matches('-');
+                match row.len() - trimmed.len() {
+                    0 => row,
+                    removed => format!("{trimmed}{removed}"),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        let player = match self.current_player {
+            Player::Bob => 'b',
+            _ => 'a',
+        };
+
+        let mut output = format!(
+            "{} {} {} {} {} {} {}",
+            self.board.kind,
+            self.rules,
+            rows,
+            player,
+            format_marble_count(self.total_marble),
+            format_marble_count(self.players_score[0]),
+            format_marble_count(self.players_score[1]),
+        );
+        if let Some(catch_data) = self
+            .sequential_move_list
+            .as_ref()
+            .and_then(|list| list.first())
+        {
+            output.push(' ');
+            output.push_str(&format_coordinate(catch_data.start_coord));
+        }
+
+        output
+    }
+
+    /// Make a game from a position string. The history of the game, which is
+    /// needed to find a repeated position, is not a part of the string.
+    pub fn from_position_string(s: &str) -> error::Result<Self> {
+        let invalid = || ZertzCoreError::InvalidNotation(s.to_string());
+
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+        let (kind, rules, rows, player, pool, alice, bob, chain) = match fields[..] {
+            [kind, rules, rows, player, pool, alice, bob] => {
+                (kind, rules, rows, player, pool, alice, bob, None)
+            }
+            [kind, rules, rows, player, pool, alice, bob, chain] => {
+                (kind, rules, rows, player, pool, alice, bob, Some(chain))
+            }
+            _ => return Err(invalid()),
+        };
+
+        let mut game = Game::with_rules(kind.parse::<BoardKind>()?, rules.parse::<Rules>()?);
+
+        let rows = rows.split('/').collect::<Vec<_>>();
+        if rows.len() != 9 {
+            return Err(invalid());
+        }
+        for (y, row) in rows.into_iter().enumerate() {
+            let mut x = 0;
+            for c in row.chars() {
+                let (ring, count) = match c {
+                    '-' => (Ring::Empty, 1),
+                    'o' => (Ring::Vacant, 1),
+                    'w' => (Ring::Occupied(Marble::White), 1),
+                    'g' => (Ring::Occupied(Marble::Gray), 1),
+                    'b' => (Ring::Occupied(Marble::Black), 1),
+                    '1'..='9' => (Ring::Empty, c as usize - '0' as usize),
+                    _ => return Err(invalid()),
+                };
+                for _ in 0..count {
+                    if x >= 9 {
+                        return Err(invalid());
+                    }
+                    game.board[Coordinate::new(x, y)] = ring;
+                    x += 1;
+                }
+            }
+            if x != 9 {
+                return Err(invalid());
+            }
+        }
+
+        game.current_player = match player {
+            "a" => Player::Alice,
+            "b" => Player::Bob,
+            _ => return Err(invalid()),
+        };
+        game.total_marble = parse_marble_count(pool)?;
+        game.players_score = [parse_marble_count(alice)?, parse_marble_count(bob)?];
+        game.calculate_components();
+
+        game.game_state = match chain {
+            Some(chain) => {
+                let start_coord = parse_coordinate(chain)?;
+                let list = game
+                    .list_all_catchable()
+                    .into_iter()
+                    .filter(|catch_data| catch_data.start_coord == start_coord)
+                    .collect::<Vec<_>>();
+                if list.is_empty() {
+                    return Err(invalid());
+                }
+                game.sequential_move_list = Some(list);
+                GameState::CatchMarble
+            }
+            None => match game.winner_by_score() {
+                Some(winner) => GameState::GameEnd(winner),
+                None => GameState::CheckIsCatchable,
+            },
+        };
+
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_round_trip() {
+        for notation in ["Wd4,a1", "Gi9", "Ba1,b2", "a1xc3", "d4xd2", "e5xc5"] {
+            assert_eq!(notation.parse::<Move>().unwrap().to_string(), notation);
+        }
+
+        let Move::Catch(catch_data) = "a1xc3".parse::<Move>().unwrap() else {
+            panic!("not a catch");
+        };
+        assert_eq!(catch_data.catched_coord, Coordinate::new(1, 1));
+    }
+
+    #[test]
+    fn invalid_moves() {
+        for notation in ["", "Xd4", "Wj1", "Wa0", "Wa10", "a1xb3", "a1xc1,", "a1xa1"] {
+            assert!(notation.parse::<Move>().is_err(), "{notation}");
+        }
+    }
+
+    #[test]
+    fn position_round_trip() {
+        let mut game = Game::new(BoardKind::Rings37);
+        assert_eq!(
+            game.to_position_string(),
+            "37 standard oooo5/ooooo4/oooooo3/ooooooo2/-oooooo2/--ooooo2/---oooo2/9/9 a 6/8/10 0/0/0 0/0/0"
+        );
+
+        for _ in 0..4 {
+            let mv = game.legal_moves()[0];
+            game.play_move(mv).unwrap();
+        }
+        let position = game.to_position_string();
+        let loaded = Game::from_position_string(&position).unwrap();
+        assert_eq!(loaded.to_position_string(), position);
+        assert_eq!(loaded.position_hash(), game.position_hash());
+        assert_eq!(loaded.legal_moves(), game.legal_moves());
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::EndReason,
+    board::BoardKind,
+    error::{self, ZertzCoreError},
+    game::{Game, Move, Player, Rules},
+};
+
+/// Every move of a game, which is enough to replay it from the empty board.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub board_kind: BoardKind,
+    #[serde(default)]
+    pub rules: Rules,
+    /// Names of Alice and Bob, which may be empty.
+    #[serde(default)]
+    pub players: [String; 2],
+    pub moves: Vec<Move>,
+    /// `None` if the game was not finished.
+    pub result: Option<Player>,
+    #[serde(default)]
+    pub reason: Option<EndReason>,
+    /// Notes on the moves, by the index of the move in `moves`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub comments: BTreeMap<usize, String>,
+}
+
+impl GameRecord {
+    pub fn new(board_kind: BoardKind, rules: Rules) -> Self {
+        Self {
+            board_kind,
+            rules,
+            ..Self::default()
+        }
+    }
+
+    pub fn load(json_str: impl AsRef<str>) -> error::Result<Self> {
+        serde_json::from_str(json_str.as_ref()).map_err(ZertzCoreError::LoadFailed)
+    }
+
+    pub fn save(&self) -> error::Result<String> {
+        serde_json::to_string(self).map_err(ZertzCoreError::SaveFailed)
+    }
+
+    /// Replay the record, calling `f` with each position and the move played
+    /// from there.
+    pub fn for_each_position(&self, mut f: impl FnMut(&Game, Move)) -> error::Result<Game> {
+        let mut game = Game::with_rules(self.board_kind, self.rules);
+
+        for &mv in self.moves.iter() {
+            f(&game, mv);
+            game.play_move(mv)?;
+        }
+
+        Ok(game)
+    }
+
+    pub fn replay(&self) -> error::Result<Game> {
+        self.for_each_position(|_, _| {})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_are_optional() {
+        let json = r#"{"board_kind":"Rings37","moves":[],"result":null}"#;
+        let record = GameRecord::load(json).unwrap();
+        assert!(record.comments.is_empty());
+        assert!(!record.save().unwrap().contains("comments"));
+
+        let record = GameRecord {
+            comments: BTreeMap::from([(0, "A quiet start.".to_string())]),
+            ..record
+        };
+        let loaded = GameRecord::load(record.save().unwrap()).unwrap();
+        assert_eq!(loaded.comments, record.comments);
+    }
+}
@@ -0,0 +1,67 @@
+pub mod engine;
+pub mod solver;
+pub mod tt;
+
+use crate::{
+    game::{Game, GameState, Player},
+    zobrist,
+};
+
+/// Score of a won position. Scores are relative to the node, so a win which
+/// is one move farther away is worth one point less.
+pub const WIN_SCORE: i32 = 1_000_000;
+pub(crate) const INFINITY: i32 = WIN_SCORE + 1;
+const MAX_PLIES_TO_END: i32 = 10_000;
+
+/// Number of plies until the end of the game if `score` is a proven win or
+/// loss.
+pub fn plies_to_end(score: i32) -> Option<u32> {
+    let plies = WIN_SCORE - score.abs();
+    (plies < MAX_PLIES_TO_END).then_some(plies as u32)
+}
+
+pub(crate) fn terminal_score(game: &Game) -> Option<i32> {
+    match game.game_state() {
+        GameState::GameEnd(Player::Tie) => Some(0),
+        GameState::GameEnd(winner) if winner == game.current_player() => Some(WIN_SCORE),
+        GameState::GameEnd(_) => Some(-WIN_SCORE),
+        _ => None,
+    }
+}
+
+// This makes searches prefer the fastest win and the slowest loss.
+#[inline]
+pub(crate) fn step_back(score: i32) -> i32 {
+    score - score.signum()
+}
+
+#[inline]
+pub(crate) fn unstep(score: i32) -> i32 {
+    score + score.signum()
+}
+
+// Whether a capture draws by repetition depends on the boards seen before,
+// so the score of a position is only reused under the same history.
+pub(crate) fn table_key(game: &Game) -> u64 {
+    game.position_hash() ^ zobrist::history_hash(game)
+}
+
+/// Game on a board which has only `rings`, where the player to move is about
+/// to check for captures.
+#[cfg(test)]
+pub(crate) fn endgame_position(rings: &[((usize, usize), crate::board::Ring)]) -> Game {
+    use crate::{
+        board::{BoardKind, Ring},
+        coordinate::Coordinate,
+    };
+
+    let mut game = Game::new(BoardKind::Rings37);
+    game.board.data = [Ring::Empty; 81];
+    for &((x, y), ring) in rings {
+        game.board[Coordinate::new(x, y)] = ring;
+    }
+    game.calculate_components();
+    game.game_state = GameState::CheckIsCatchable;
+
+    game
+}
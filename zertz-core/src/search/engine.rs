@@ -0,0 +1,443 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    board::Marble,
+    book::{BookSelection, OpeningBook},
+    game::{Game, MarbleCount, Move, Rules},
+};
+
+use super::{
+    plies_to_end, step_back, table_key, terminal_score,
+    tt::{Bound, ReplacementPolicy, TranspositionTable},
+    unstep, INFINITY,
+};
+
+pub const MAX_DEPTH: u8 = 64;
+const MARBLES: [Marble; 3] = [Marble::White, Marble::Gray, Marble::Black];
+const STOP_CHECK_INTERVAL: u64 = 256;
+
+/// Limits of a single search. A search without any limit runs until
+/// [`MAX_DEPTH`] or until it is stopped through [`Engine::stop_handle`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchLimits {
+    pub depth: Option<u8>,
+    pub move_time: Option<Duration>,
+    pub nodes: Option<u64>,
+}
+
+/// Progress of a search, reported after each finished iteration.
+#[derive(Debug, Clone)]
+pub struct SearchInfo {
+    pub depth: u8,
+    pub score: i32,
+    pub nodes: u64,
+    pub elapsed: Duration,
+    pub principal_line: Vec<Move>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchResult {
+    pub best_move: Option<Move>,
+    pub score: i32,
+    pub depth: u8,
+    pub nodes: u64,
+    pub principal_line: Vec<Move>,
+    pub from_book: bool,
+}
+
+/// Iterative deepening alpha-beta searcher, which is the computer player.
+pub struct Engine {
+    tt: TranspositionTable,
+    book: Option<OpeningBook>,
+    book_selection: BookSelection,
+    rng: StdRng,
+    stop: Arc<AtomicBool>,
+    nodes: u64,
+    node_limit: u64,
+    deadline: Option<Instant>,
+    aborted: bool,
+}
+
+impl Engine {
+    pub fn new(hash_size_mb: usize) -> Self {
+        Self {
+            tt: TranspositionTable::new(hash_size_mb, ReplacementPolicy::DepthPreferred),
+            book: None,
+            book_selection: BookSelection::default(),
+            rng: StdRng::from_entropy(),
+            stop: Arc::new(AtomicBool::new(false)),
+            nodes: 0,
+            node_limit: u64::MAX,
+            deadline: None,
+            aborted: false,
+        }
+    }
+
+    pub fn set_hash_size(&mut self, hash_size_mb: usize) {
+        self.tt.resize(hash_size_mb);
+    }
+
+    pub fn set_book(&mut self, book: Option<OpeningBook>, book_selection: BookSelection) {
+        self.book = book;
+        self.book_selection = book_selection;
+    }
+
+    /// Make the choice among book moves reproducible.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Setting the returned flag to `true` stops the running search, which
+    /// then returns the result of its last finished iteration. The flag is
+    /// cleared when the search returns.
+    pub fn stop_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    /// Forget everything learned from previous searches.
+    pub fn clear(&mut self) {
+        self.tt.clear();
+    }
+
+    pub fn hashfull(&self) -> usize {
+        self.tt.hashfull()
+    }
+
+    pub fn search(
+        &mut self,
+        game: &Game,
+        limits: SearchLimits,
+        on_info: impl FnMut(&SearchInfo),
+    ) -> SearchResult {
+        let output = self.iterative_deepening(game, limits, on_info);
+        self.stop.store(false, Ordering::Relaxed);
+
+        output
+    }
+
+    fn iterative_deepening(
+        &mut self,
+        game: &Game,
+        limits: SearchLimits,
+        mut on_info: impl FnMut(&SearchInfo),
+    ) -> SearchResult {
+        let start = Instant::now();
+        self.nodes = 0;
+        self.node_limit = limits.nodes.unwrap_or(u64::MAX);
+        self.deadline = limits.move_time.map(|move_time| start + move_time);
+        self.aborted = false;
+
+        if let Some(mv) = self
+            .book
+            .as_ref()
+            .and_then(|book| book.pick_move(game, &mut self.rng, self.book_selection))
+        {
+            return SearchResult {
+                best_move: Some(mv),
+                principal_line: vec![mv],
+                from_book: true,
+                ..SearchResult::default()
+            };
+        }
+
+        let mut moves = game.legal_moves();
+        let mut output = SearchResult {
+            best_move: moves.first().copied(),
+            ..SearchResult::default()
+        };
+        if moves.len() <= 1 {
+            return output;
+        }
+
+        self.tt.new_search();
+
+        for depth in 1..=limits.depth.unwrap_or(MAX_DEPTH).min(MAX_DEPTH) {
+            let Some((score, best_move)) = self.search_root(game, &moves, depth) else {
+                break;
+            };
+
+            // Search the best move of this iteration first in the next one.
+            if let Some(idx) = moves.iter().position(|mv| *mv == best_move) {
+                moves[..=idx].rotate_right(1);
+            }
+
+            let mut principal_line = vec![best_move];
+            let mut child = game.clone();
+            if child.play_move(best_move).is_ok() {
+                principal_line.extend(self.principal_line(child, depth - 1));
+            }
+
+            output = SearchResult {
+                best_move: Some(best_move),
+                score,
+                depth,
+                nodes: self.nodes,
+                principal_line,
+                from_book: false,
+            };
+            on_info(&SearchInfo {
+                depth,
+                score,
+                nodes: self.nodes,
+                elapsed: start.elapsed(),
+                principal_line: output.principal_line.clone(),
+            });
+
+            if self.aborted || plies_to_end(score).is_some() {
+                break;
+            }
+        }
+
+        output.nodes = self.nodes;
+        output
+    }
+
+    fn search_root(&mut self, game: &Game, moves: &[Move], depth: u8) -> Option<(i32, Move)> {
+        let mut alpha = -INFINITY;
+        let mut best_move = moves[0];
+
+        for &mv in moves {
+            let score = self.child_score(game, mv, depth - 1, alpha, INFINITY);
+            if self.aborted {
+                return None;
+            }
+            if score > alpha {
+                alpha = score;
+                best_move = mv;
+            }
+        }
+
+        let key = table_key(game);
+        self.tt
+            .store(key, depth, Bound::Exact, alpha, Some(best_move));
+
+        Some((alpha, best_move))
+    }
+
+    fn negamax(&mut self, game: &Game, depth: u8, mut alpha: i32, mut beta: i32) -> i32 {
+        self.nodes += 1;
+        if self.nodes.is_multiple_of(STOP_CHECK_INTERVAL) && self.should_stop() {
+            self.aborted = true;
+        }
+        if self.aborted {
+            return 0;
+        }
+
+        if let Some(score) = terminal_score(game) {
+            return score;
+        }
+
+        let mut moves = game.legal_moves();
+        if moves.is_empty() {
+            return 0;
+        }
+        // Catching a marble is compulsory, so the search goes on until every
+        // sequence of captures is finished instead of stopping in the middle.
+        if depth == 0 && !matches!(moves[0], Move::Catch(_)) {
+            return evaluate(game);
+        }
+
+        let key = table_key(game);
+        if let Some(entry) = self.tt.probe(key) {
+            if entry.depth >= depth {
+                match entry.bound {
+                    Bound::Exact => return entry.score,
+                    Bound::Lower => alpha = alpha.max(entry.score),
+                    Bound::Upper => beta = beta.min(entry.score),
+                }
+                if alpha >= beta {
+                    return entry.score;
+                }
+            }
+            if let Some(idx) = entry
+                .best_move
+                .and_then(|best_move| moves.iter().position(|mv| *mv == best_move))
+            {
+                moves.swap(0, idx);
+            }
+        }
+
+        let alpha_orig = alpha;
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+
+        for mv in moves {
+            let score = self.child_score(game, mv, depth.saturating_sub(1), alpha, beta);
+            if self.aborted {
+                return 0;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_move = Some(mv);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best_score <= alpha_orig {
+            Bound::Upper
+        } else if best_score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.tt.store(key, depth, bound, best_score, best_move);
+
+        best_score
+    }
+
+    // A capture sequence keeps the same player to move, so the score is
+    // negated only if the turn passes to the other player.
+    fn child_score(&mut self, game: &Game, mv: Move, depth: u8, alpha: i32, beta: i32) -> i32 {
+        let mut child = game.clone();
+        if child.play_move(mv).is_err() {
+            return -INFINITY;
+        }
+
+        let score = if child.current_player() == game.current_player() {
+            self.negamax(&child, depth, unstep(alpha), unstep(beta))
+        } else {
+            -self.negamax(&child, depth, -unstep(beta), -unstep(alpha))
+        };
+
+        step_back(score)
+    }
+
+    fn principal_line(&self, mut game: Game, max_len: u8) -> Vec<Move> {
+        let mut output = Vec::with_capacity(max_len as usize);
+
+        for _ in 0..max_len {
+            let Some(mv) = self
+                .tt
+                .probe(table_key(&game))
+                .and_then(|entry| entry.best_move)
+            else {
+                break;
+            };
+            if game.play_move(mv).is_err() {
+                break;
+            }
+            output.push(mv);
+        }
+
+        output
+    }
+
+    fn should_stop(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+            || self.nodes >= self.node_limit
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// Static evaluation from the view of the player to move.
+fn evaluate(game: &Game) -> i32 {
+    let players_score = game.players_score();
+    let player = game.current_player();
+
+    progress(players_score[usize::from(player)], game.rules())
+        - progress(players_score[usize::from(player.opponent())], game.rules())
+}
+
+// How close `score` is to the win condition, where 1000 means a win.
+fn progress(score: MarbleCount, rules: Rules) -> i32 {
+    let win_condition = rules.win_condition();
+
+    let by_color = MARBLES
+        .iter()
+        .map(|&marble| score.get(marble) * 1000 / win_condition.get(marble))
+        .max()
+        .unwrap_or_default();
+    let by_each = MARBLES
+        .iter()
+        .map(|&marble| score.get(marble).min(win_condition.each))
+        .sum::<usize>()
+        * 1000
+        / (3 * win_condition.each);
+    let total = MARBLES
+        .iter()
+        .map(|&marble| score.get(marble))
+        .sum::<usize>();
+
+    (by_color.max(by_each) + 10 * total) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::{BoardKind, Ring},
+        game::{Game, GameState, Player},
+        search::endgame_position,
+    };
+
+    #[test]
+    fn search_returns_legal_move() {
+        let game = Game::new(BoardKind::Rings37);
+        let limits = SearchLimits {
+            depth: Some(1),
+            ..SearchLimits::default()
+        };
+
+        let result = Engine::new(1).search(&game, limits, |_| {});
+        assert!(game.legal_moves().contains(&result.best_move.unwrap()));
+        assert_eq!(result.depth, 1);
+    }
+
+    #[test]
+    fn find_winning_capture() {
+        let mut game = endgame_position(&[
+            ((0, 0), Ring::Occupied(Marble::White)),
+            ((1, 0), Ring::Occupied(Marble::White)),
+            ((2, 0), Ring::Vacant),
+            ((0, 1), Ring::Occupied(Marble::Gray)),
+            ((0, 2), Ring::Vacant),
+        ]);
+        for _ in 0..3 {
+            game.players_score[usize::from(Player::Alice)].inc(Marble::White);
+        }
+
+        let result = Engine::new(1).search(&game, SearchLimits::default(), |_| {});
+        let mut child = game.clone();
+        child.play_move(result.best_move.unwrap()).unwrap();
+        assert!(matches!(
+            child.game_state(),
+            GameState::GameEnd(Player::Alice)
+        ));
+        assert_eq!(plies_to_end(result.score), Some(1));
+    }
+
+    #[test]
+    fn repetition_does_not_leak_into_other_histories() {
+        let mut game = endgame_position(&[
+            ((0, 0), Ring::Occupied(Marble::White)),
+            ((1, 0), Ring::Occupied(Marble::Gray)),
+            ((2, 0), Ring::Vacant),
+            ((3, 0), Ring::Occupied(Marble::White)),
+            ((4, 0), Ring::Vacant),
+        ]);
+        for _ in 0..3 {
+            game.players_score[0].inc(Marble::White);
+        }
+        // The first capture of the chain brings back a recorded board for the
+        // third time, which draws before the winning second capture.
+        let mut repeated = game.clone();
+        let mut after = game.clone();
+        after.play_move(game.legal_moves()[0]).unwrap();
+        repeated.board_replace_history.push(after.board);
+        repeated.repeat_count.set(2);
+
+        let mut engine = Engine::new(1);
+        assert_eq!(engine.negamax(&repeated, 4, -INFINITY, INFINITY), 0);
+        assert!(plies_to_end(engine.negamax(&game, 4, -INFINITY, INFINITY)).is_some());
+    }
+}
@@ -0,0 +1,261 @@
+use crate::{
+    error::{self, ZertzCoreError},
+    game::{Game, Move, Player},
+};
+
+use super::{
+    step_back, table_key, terminal_score,
+    tt::{Bound, ReplacementPolicy, TranspositionTable},
+    unstep, INFINITY,
+};
+
+const DEFAULT_TABLE_SIZE_MB: usize = 16;
+// Every entry of the solver is searched to the end of the game.
+const SOLVED_DEPTH: u8 = u8::MAX;
+
+/// Result of a solved position, seen from the player to move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+#[derive(Debug, Clone)]
+pub struct Solution {
+    pub outcome: Outcome,
+    pub winner: Player,
+    pub principal_line: Vec<Move>,
+    pub nodes: u64,
+}
+
+/// Exhaustive solver for positions near the end of a game.
+///
+/// Positions are memorised in a [`TranspositionTable`] which is kept between
+/// calls of [`Solver::solve`], so solving the positions of a single game one
+/// after another is cheap.
+pub struct Solver {
+    tt: TranspositionTable,
+    node_budget: u64,
+    nodes: u64,
+}
+
+impl Solver {
+    pub fn new(node_budget: u64) -> Self {
+        Self::with_table_size(node_budget, DEFAULT_TABLE_SIZE_MB)
+    }
+
+    pub fn with_table_size(node_budget: u64, size_mb: usize) -> Self {
+        Self {
+            tt: TranspositionTable::new(size_mb, ReplacementPolicy::DepthPreferred),
+            node_budget,
+            nodes: 0,
+        }
+    }
+
+    /// Prove the result of `game`. This returns
+    /// [`ZertzCoreError::NodeBudgetExceeded`] instead of a result if more than
+    /// `node_budget` nodes should be visited.
+    ///
+    /// A position without any legal move, which only happens if nobody has a
+    /// marble to put, is counted as a draw.
+    pub fn solve(&mut self, game: &Game) -> error::Result<Solution> {
+        self.nodes = 0;
+        self.tt.new_search();
+
+        let score = self.negamax(game, -INFINITY, INFINITY)?;
+        let principal_line = self.principal_line(game, score)?;
+
+        let (outcome, winner) = match score.signum() {
+            1 => (Outcome::Win, game.current_player()),
+            -1 => (Outcome::Loss, game.current_player().opponent()),
+            _ => (Outcome::Draw, Player::Tie),
+        };
+
+        Ok(Solution {
+            outcome,
+            winner,
+            principal_line,
+            nodes: self.nodes,
+        })
+    }
+
+    fn negamax(&mut self, game: &Game, mut alpha: i32, mut beta: i32) -> error::Result<i32> {
+        self.nodes += 1;
+        if self.nodes > self.node_budget {
+            return Err(ZertzCoreError::NodeBudgetExceeded(self.node_budget));
+        }
+
+        if let Some(score) = terminal_score(game) {
+            return Ok(score);
+        }
+
+        let mut moves = game.legal_moves();
+        if moves.is_empty() {
+            return Ok(0);
+        }
+
+        let key = table_key(game);
+        if let Some(entry) = self.tt.probe(key) {
+            match entry.bound {
+                Bound::Exact => return Ok(entry.score),
+                Bound::Lower => alpha = alpha.max(entry.score),
+                Bound::Upper => beta = beta.min(entry.score),
+            }
+            if alpha >= beta {
+                return Ok(entry.score);
+            }
+            if let Some(idx) = entry
+                .best_move
+                .and_then(|best_move| moves.iter().position(|mv| *mv == best_move))
+            {
+                moves.swap(0, idx);
+            }
+        }
+
+        let alpha_orig = alpha;
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+
+        for mv in moves {
+            let score = self.child_score(game, mv, alpha, beta)?;
+
+            if score > best_score {
+                best_score = score;
+                best_move = Some(mv);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best_score <= alpha_orig {
+            Bound::Upper
+        } else if best_score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.tt
+            .store(key, SOLVED_DEPTH, bound, best_score, best_move);
+
+        Ok(best_score)
+    }
+
+    // Score of `mv` seen from the player to move in `game`. A capture sequence
+    // keeps the same player to move, so the score is negated only if the turn
+    // passes to the other player.
+    fn child_score(&mut self, game: &Game, mv: Move, alpha: i32, beta: i32) -> error::Result<i32> {
+        let mut child = game.clone();
+        child.play_move(mv)?;
+
+        let score = if child.current_player() == game.current_player() {
+            self.negamax(&child, unstep(alpha), unstep(beta))?
+        } else {
+            -self.negamax(&child, -unstep(beta), -unstep(alpha))?
+        };
+
+        Ok(step_back(score))
+    }
+
+    fn principal_line(&mut self, game: &Game, score: i32) -> error::Result<Vec<Move>> {
+        let mut output = Vec::new();
+        let mut game = game.clone();
+        let mut score = score;
+
+        while terminal_score(&game).is_none() {
+            let mut moves = game.legal_moves();
+            if let Some(idx) = self
+                .tt
+                .probe(table_key(&game))
+                .and_then(|entry| entry.best_move)
+                .and_then(|best_move| moves.iter().position(|mv| *mv == best_move))
+            {
+                moves.swap(0, idx);
+            }
+
+            let mut next = None;
+            for mv in moves {
+                if self.child_score(&game, mv, -INFINITY, INFINITY)? == score {
+                    next = Some(mv);
+                    break;
+                }
+            }
+
+            let Some(mv) = next else { break };
+            let prev_player = game.current_player();
+            game.play_move(mv)?;
+            output.push(mv);
+
+            score = unstep(score);
+            if game.current_player() != prev_player {
+                score = -score;
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::{BoardKind, Marble, Ring},
+        search::endgame_position,
+    };
+
+    #[test]
+    fn capture_to_win() {
+        let mut game = endgame_position(&[
+            ((0, 0), Ring::Occupied(Marble::White)),
+            ((1, 0), Ring::Occupied(Marble::White)),
+            ((2, 0), Ring::Vacant),
+            ((3, 0), Ring::Vacant),
+        ]);
+        for _ in 0..3 {
+            game.players_score[0].inc(Marble::White);
+        }
+
+        let solution = Solver::new(1_000).solve(&game).unwrap();
+        assert_eq!(solution.outcome, Outcome::Win);
+        assert_eq!(solution.winner, Player::Alice);
+        assert_eq!(solution.principal_line.len(), 1);
+    }
+
+    #[test]
+    fn repetition_does_not_leak_into_other_histories() {
+        let mut game = endgame_position(&[
+            ((0, 0), Ring::Occupied(Marble::White)),
+            ((1, 0), Ring::Occupied(Marble::Gray)),
+            ((2, 0), Ring::Vacant),
+            ((3, 0), Ring::Occupied(Marble::White)),
+            ((4, 0), Ring::Vacant),
+        ]);
+        for _ in 0..3 {
+            game.players_score[0].inc(Marble::White);
+        }
+        // The first capture of the chain brings back a recorded board for the
+        // third time, which draws before the winning second capture.
+        let mut repeated = game.clone();
+        let mut after = game.clone();
+        after.play_move(game.legal_moves()[0]).unwrap();
+        repeated.board_replace_history.push(after.board);
+        repeated.repeat_count.set(2);
+
+        let mut solver = Solver::new(1_000);
+        assert_eq!(solver.solve(&repeated).unwrap().outcome, Outcome::Draw);
+        assert_eq!(solver.solve(&game).unwrap().outcome, Outcome::Win);
+    }
+
+    #[test]
+    fn node_budget_is_respected() {
+        let game = Game::new(BoardKind::Rings37);
+
+        assert!(matches!(
+            Solver::new(100).solve(&game),
+            Err(ZertzCoreError::NodeBudgetExceeded(100))
+        ));
+    }
+}
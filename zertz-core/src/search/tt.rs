@@ -0,0 +1,196 @@
+use std::mem;
+
+use crate::game::Move;
+
+const MEGABYTE: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReplacementPolicy {
+    /// Always overwrite the slot with the newest entry.
+    Always,
+    /// Keep a deeper entry of the current search, but overwrite anything
+    /// left over from a previous search.
+    #[default]
+    DepthPreferred,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TtEntry {
+    pub key: u64,
+    pub depth: u8,
+    pub bound: Bound,
+    pub score: i32,
+    pub best_move: Option<Move>,
+    generation: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct TranspositionTable {
+    entries: Vec<Option<TtEntry>>,
+    policy: ReplacementPolicy,
+    generation: u8,
+}
+
+impl TranspositionTable {
+    /// Make a table which uses at most `size_mb` megabytes. The number of
+    /// slots is rounded down to a power of two, but there is always at least
+    /// one slot.
+    pub fn new(size_mb: usize, policy: ReplacementPolicy) -> Self {
+        Self {
+            entries: vec![None; Self::slot_count(size_mb)],
+            policy,
+            generation: 0,
+        }
+    }
+
+    fn slot_count(size_mb: usize) -> usize {
+        let slots = size_mb.saturating_mul(MEGABYTE) / mem::size_of::<Option<TtEntry>>();
+        if slots == 0 {
+            1
+        } else {
+            1 << slots.ilog2()
+        }
+    }
+
+    #[inline]
+    fn index(&self, key: u64) -> usize {
+        key as usize & (self.entries.len() - 1)
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn policy(&self) -> ReplacementPolicy {
+        self.policy
+    }
+
+    pub fn resize(&mut self, size_mb: usize) {
+        self.entries = vec![None; Self::slot_count(size_mb)];
+        self.generation = 0;
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+        self.generation = 0;
+    }
+
+    /// Mark the start of a new search, so that entries stored before are
+    /// preferred for replacement.
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    pub fn probe(&self, key: u64) -> Option<&TtEntry> {
+        self.entries[self.index(key)]
+            .as_ref()
+            .filter(|entry| entry.key == key)
+    }
+
+    pub fn store(
+        &mut self,
+        key: u64,
+        depth: u8,
+        bound: Bound,
+        score: i32,
+        best_move: Option<Move>,
+    ) {
+        let idx = self.index(key);
+        let generation = self.generation;
+
+        if let (ReplacementPolicy::DepthPreferred, Some(entry)) = (self.policy, &self.entries[idx])
+        {
+            if entry.generation == generation && entry.key != key && entry.depth > depth {
+                return;
+            }
+        }
+
+        // Do not lose the best move of a position if a shallower search of the
+        // very same position could not find one.
+        let best_move = match &self.entries[idx] {
+            Some(entry) if entry.key == key && best_move.is_none() => entry.best_move,
+            _ => best_move,
+        };
+
+        self.entries[idx] = Some(TtEntry {
+            key,
+            depth,
+            bound,
+            score,
+            best_move,
+            generation,
+        });
+    }
+
+    /// Approximate occupation of the table in permill, counting only the
+    /// entries of the current search.
+    pub fn hashfull(&self) -> usize {
+        let sample = self.entries.len().min(1000);
+        let used = self.entries[..sample]
+            .iter()
+            .flatten()
+            .filter(|entry| entry.generation == self.generation)
+            .count();
+
+        used * 1000 / sample
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_is_power_of_two() {
+        let tt = TranspositionTable::new(1, ReplacementPolicy::default());
+        assert!(tt.capacity().is_power_of_two());
+        assert!(tt.capacity() * mem::size_of::<Option<TtEntry>>() <= MEGABYTE);
+
+        let tt = TranspositionTable::new(0, ReplacementPolicy::default());
+        assert_eq!(tt.capacity(), 1);
+    }
+
+    #[test]
+    fn store_and_probe() {
+        let mut tt = TranspositionTable::new(1, ReplacementPolicy::default());
+        tt.store(42, 3, Bound::Exact, 17, None);
+
+        let entry = tt.probe(42).unwrap();
+        assert_eq!(
+            (entry.depth, entry.bound, entry.score),
+            (3, Bound::Exact, 17)
+        );
+        assert!(tt.probe(43).is_none());
+    }
+
+    #[test]
+    fn depth_preferred_replacement() {
+        let mut tt = TranspositionTable::new(0, ReplacementPolicy::DepthPreferred);
+        tt.store(1, 5, Bound::Exact, 10, None);
+        tt.store(2, 2, Bound::Lower, 20, None);
+        assert_eq!(tt.probe(1).unwrap().score, 10);
+        assert!(tt.probe(2).is_none());
+
+        tt.new_search();
+        tt.store(2, 2, Bound::Lower, 20, None);
+        assert_eq!(tt.probe(2).unwrap().score, 20);
+    }
+
+    #[test]
+    fn always_replacement() {
+        let mut tt = TranspositionTable::new(0, ReplacementPolicy::Always);
+        tt.store(1, 5, Bound::Exact, 10, None);
+        tt.store(2, 2, Bound::Upper, 20, None);
+        assert!(tt.probe(1).is_none());
+        assert_eq!(tt.probe(2).unwrap().bound, Bound::Upper);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{
+    board::{Marble, Ring},
+    coordinate::CoordinateIter,
+    game::{Game, GameState, Player},
+};
+
+const MARBLES: [Marble; 3] = [Marble::White, Marble::Gray, Marble::Black];
+const MAX_MARBLE_COUNT: usize = 16;
+
+struct ZobristKeys {
+    rings: [[u64; 5]; 81],
+    total_marble: [[u64; MAX_MARBLE_COUNT]; 3],
+    players_score: [[[u64; MAX_MARBLE_COUNT]; 3]; 2],
+    sequential_move: [u64; 81],
+    bob_to_move: u64,
+    game_end: u64,
+}
+
+// Keys are generated at compile time so that every build (and therefore every
+// opening book or saved table) agrees on the hash of a position.
+static KEYS: ZobristKeys = ZobristKeys::new(0x5A45_5254_5A5F_4B45);
+
+impl ZobristKeys {
+    const fn new(seed: u64) -> Self {
+        let mut state = seed;
+        let mut output = Self {
+            rings: [[0; 5]; 81],
+            total_marble: [[0; MAX_MARBLE_COUNT]; 3],
+            players_score: [[[0; MAX_MARBLE_COUNT]; 3]; 2],
+            sequential_move: [0; 81],
+            bob_to_move: 0,
+            game_end: 0,
+        };
+
+        let mut i = 0;
+        while i < 81 {
+            let mut j = 0;
+            while j < 5 {
+                (state, output.rings[i][j]) = splitmix64(state);
+                j += 1;
+            }
+            (state, output.sequential_move[i]) = splitmix64(state);
+            i += 1;
+        }
+
+        let mut i = 0;
+        while i < 3 {
+            let mut j = 0;
+            while j < MAX_MARBLE_COUNT {
+                (state, output.total_marble[i][j]) = splitmix64(state);
+                (state, output.players_score[0][i][j]) = splitmix64(state);
+                (state, output.players_score[1][i][j]) = splitmix64(state);
+                j += 1;
+            }
+            i += 1;
+        }
+
+        (state, output.bob_to_move) = splitmix64(state);
+        (_, output.game_end) = splitmix64(state);
+
+        output
+    }
+}
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+#[inline]
+fn ring_index(ring: Ring) -> usize {
+    match ring {
+        Ring::Empty => 0,
+        Ring::Vacant => 1,
+        Ring::Occupied(Marble::White) => 2,
+        Ring::Occupied(Marble::Gray) => 3,
+        Ring::Occupied(Marble::Black) => 4,
+    }
+}
+
+#[inline]
+fn count_index(count: usize) -> usize {
+    count.min(MAX_MARBLE_COUNT - 1)
+}
+
+pub(crate) fn hash(game: &Game) -> u64 {
+    let mut output = 0;
+
+    for coord in CoordinateIter::new() {
+        output ^= KEYS.rings[usize::from(coord)][ring_index(game.board[coord])];
+    }
+
+    for (idx, marble) in MARBLES.into_iter().enumerate() {
+        output ^= KEYS.total_marble[idx][count_index(game.total_marble.get(marble))];
+        for (player, score) in game.players_score.iter().enumerate() {
+            output ^= KEYS.players_score[player][idx][count_index(score.get(marble))];
+        }
+    }
+
+    if let Player::Bob = game.current_player {
+        output ^= KEYS.bob_to_move;
+    }
+    if let Some(catch_data) = game
+        .sequential_move_list
+        .as_ref()
+        .and_then(|list| list.first())
+    {
+        output ^= KEYS.sequential_move[usize::from(catch_data.start_coord)];
+    }
+    if let GameState::GameEnd(_) = game.game_state {
+        output ^= KEYS.game_end;
+    }
+
+    output
+}
+
+/// Hash of what a draw by repetition depends on: the boards recorded since
+/// the last ring removal and the repetitions counted so far. This is zero if
+/// nothing was recorded, and is left out of [`hash`] so that transpositions
+/// still meet.
+pub(crate) fn history_hash(game: &Game) -> u64 {
+    if game.board_replace_history.is_empty() && game.repeat_count.get() == 0 {
+        return 0;
+    }
+
+    let mut output = game.repeat_count.get() as u64;
+    for board in game.board_replace_history.iter() {
+        let board_hash = CoordinateIter::new().fold(0, |acc, coord| {
+            acc ^ KEYS.rings[usize::from(coord)][ring_index(board[coord])]
+        });
+        // Mixing keeps a board recorded twice from cancelling itself out.
+        (_, output) = splitmix64(output ^ board_hash);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{board::BoardKind, coordinate::Coordinate, game::Move};
+
+    fn put(put_coord: (usize, usize), remove_coord: (usize, usize), marble: Marble) -> Move {
+        Move::Put {
+            put_coord: Coordinate::new(put_coord.0, put_coord.1),
+            remove_coord: Some(Coordinate::new(remove_coord.0, remove_coord.1)),
+            marble,
+        }
+    }
+
+    #[test]
+    fn transposed_placements_share_hash() {
+        let mut first = Game::new(BoardKind::Rings37);
+        first.play_move(put((2, 2), (0, 0), Marble::White)).unwrap();
+        first.play_move(put((4, 4), (6, 6), Marble::Black)).unwrap();
+
+        let mut second = Game::new(BoardKind::Rings37);
+        second
+            .play_move(put((2, 2), (6, 6), Marble::White))
+            .unwrap();
+        second
+            .play_move(put((4, 4), (0, 0), Marble::Black))
+            .unwrap();
+
+        assert_eq!(first.position_hash(), second.position_hash());
+    }
+
+    #[test]
+    fn hash_depends_on_player_to_move() {
+        let game = Game::new(BoardKind::Rings37);
+        let mut other = game.clone();
+        other.current_player = Player::Bob;
+
+        assert_ne!(game.position_hash(), other.position_hash());
+    }
+}
@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ZertzEngineError {
+    #[error("{0}")]
+    IOErr(#[from] std::io::Error),
+    #[error("{0}")]
+    ZertzCoreErr(#[from] zertz_core::error::ZertzCoreError),
+    #[error("unknown command: {0}")]
+    UnknownCommand(String),
+    #[error("unknown option: {0}")]
+    UnknownOption(String),
+    #[error("invalid value for {0}: {1}")]
+    InvalidValue(String, String),
+    #[error("the command is not complete: {0}")]
+    MissingArgument(String),
+}
+
+pub type Result<T> = std::result::Result<T, ZertzEngineError>;
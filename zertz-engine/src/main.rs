@@ -0,0 +1,24 @@
+//! Engine speaking the line protocol which is described in `PROTOCOL.md`.
+
+mod error;
+mod protocol;
+mod session;
+
+use std::io::{self, BufRead};
+
+use session::Session;
+
+fn main() -> error::Result<()> {
+    let mut session = Session::new(io::stdout());
+
+    for line in io::stdin().lock().lines() {
+        if !session.handle_line(&line?)? {
+            return Ok(());
+        }
+    }
+
+    // The input is closed, but a search of a piped script can still finish.
+    session.wait_for_search();
+
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use zertz_core::game::Move;
+
+use crate::error::{self, ZertzEngineError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Position {
+    StartPos,
+    /// A position string of `zertz_core::notation`.
+    Pos(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GoParams {
+    pub depth: Option<u8>,
+    pub move_time: Option<Duration>,
+    pub nodes: Option<u64>,
+    /// Remaining time of Alice and Bob.
+    pub time: [Option<Duration>; 2],
+    /// Time added after each move of Alice and Bob.
+    pub increment: [Duration; 2],
+    pub moves_to_go: Option<u32>,
+    pub infinite: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Zei,
+    IsReady,
+    SetOption {
+        name: String,
+        value: String,
+    },
+    NewGame,
+    Position {
+        position: Position,
+        moves: Vec<Move>,
+    },
+    Go(GoParams),
+    Stop,
+    Print,
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = ZertzEngineError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut tokens = line.split_whitespace();
+
+        match tokens.next() {
+            Some("zei") => Ok(Self::Zei),
+            Some("isready") => Ok(Self::IsReady),
+            Some("setoption") => parse_setoption(line, tokens.collect()),
+            Some("newgame") => Ok(Self::NewGame),
+            Some("position") => parse_position(line, tokens.collect()),
+            Some("go") => parse_go(tokens.collect()).map(Self::Go),
+            Some("stop") => Ok(Self::Stop),
+            Some("print") => Ok(Self::Print),
+            Some("quit") => Ok(Self::Quit),
+            _ => Err(ZertzEngineError::UnknownCommand(line.to_string())),
+        }
+    }
+}
+
+// setoption name <name> value <value>
+fn parse_setoption(line: &str, tokens: Vec<&str>) -> error::Result<Command> {
+    match tokens[..] {
+        ["name", name, "value", ref value @ ..] if !value.is_empty() => Ok(Command::SetOption {
+            name: name.to_string(),
+            value: value.join(" "),
+        }),
+        _ => Err(ZertzEngineError::MissingArgument(line.to_string())),
+    }
+}
+
+// position (startpos | pos <position string>) [moves <move>...]
+fn parse_position(line: &str, tokens: Vec<&str>) -> error::Result<Command> {
+    let (position, rest) = match tokens.iter().position(|token| *token == "moves") {
+        Some(idx) => (&tokens[..idx], &tokens[idx + 1..]),
+        None => (&tokens[..], &[][..]),
+    };
+
+    let position = match position {
+        ["startpos"] => Position::StartPos,
+        ["pos", fields @ ..] if !fields.is_empty() => Position::Pos(fields.join(" ")),
+        _ => return Err(ZertzEngineError::MissingArgument(line.to_string())),
+    };
+    let moves = rest
+        .iter()
+        .map(|token| token.parse::<Move>())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Command::Position { position, moves })
+}
+
+fn parse_go(tokens: Vec<&str>) -> error::Result<GoParams> {
+    let mut output = GoParams::default();
+    let mut tokens = tokens.into_iter();
+
+    while let Some(key) = tokens.next() {
+        if key == "infinite" {
+            output.infinite = true;
+            continue;
+        }
+
+        let value = tokens
+            .next()
+            .ok_or_else(|| ZertzEngineError::MissingArgument(format!("go {key}")))?;
+        let millis = || parse_value::<u64>(key, value).map(Duration::from_millis);
+
+        match key {
+            "depth" => output.depth = Some(parse_value(key, value)?),
+            "movetime" => output.move_time = Some(millis()?),
+            "nodes" => output.nodes = Some(parse_value(key, value)?),
+            "atime" => output.time[0] = Some(millis()?),
+            "btime" => output.time[1] = Some(millis()?),
+            "ainc" => output.increment[0] = millis()?,
+            "binc" => output.increment[1] = millis()?,
+            "movestogo" => output.moves_to_go = Some(parse_value(key, value)?),
+            _ => return Err(ZertzEngineError::UnknownCommand(format!("go {key}"))),
+        }
+    }
+
+    Ok(output)
+}
+
+pub fn parse_value<T: FromStr>(key: &str, value: &str) -> error::Result<T> {
+    value
+        .parse()
+        .map_err(|_| ZertzEngineError::InvalidValue(key.to_string(), value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!("zei".parse::<Command>().unwrap(), Command::Zei);
+        assert_eq!(
+            "setoption name Rules value blitz"
+                .parse::<Command>()
+                .unwrap(),
+            Command::SetOption {
+                name: "Rules".to_string(),
+                value: "blitz".to_string()
+            }
+        );
+        assert!("setoption name Rules".parse::<Command>().is_err());
+        assert!("jump".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn parse_position() {
+        let Command::Position { position, moves } = "position startpos moves Wd4,a1 Ga1"
+            .parse::<Command>()
+            .unwrap()
+        else {
+            panic!("not a position");
+        };
+        assert_eq!(position, Position::StartPos);
+        assert_eq!(moves.len(), 2);
+
+        let Command::Position { position, moves } =
+            "position pos 37 standard 9/9/9/9/9/9/9/9/9 a 6/8/10 0/0/0 0/0/0"
+                .parse::<Command>()
+                .unwrap()
+        else {
+            panic!("not a position");
+        };
+        assert_eq!(
+            position,
+            Position::Pos("37 standard 9/9/9/9/9/9/9/9/9 a 6/8/10 0/0/0 0/0/0".to_string())
+        );
+        assert!(moves.is_empty());
+
+        assert!("position startpos moves Qd4".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn parse_go() {
+        let Command::Go(params) = "go atime 60000 btime 30000 binc 500 movestogo 10"
+            .parse::<Command>()
+            .unwrap()
+        else {
+            panic!("not a go");
+        };
+        assert_eq!(
+            params.time,
+            [Some(Duration::from_secs(60)), Some(Duration::from_secs(30))]
+        );
+        assert_eq!(params.increment[1], Duration::from_millis(500));
+        assert_eq!(params.moves_to_go, Some(10));
+
+        assert!("go depth".parse::<Command>().is_err());
+        assert!("go depth deep".parse::<Command>().is_err());
+    }
+}
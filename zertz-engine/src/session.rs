@@ -0,0 +1,391 @@
+use std::fs;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use zertz_core::{
+    board::BoardKind,
+    book::{BookSelection, OpeningBook},
+    game::{Game, Player, Rules},
+    search::{
+        engine::{Engine, SearchInfo, SearchLimits},
+        plies_to_end,
+    },
+};
+
+use crate::{
+    error::{self, ZertzEngineError},
+    protocol::{parse_value, Command, GoParams, Position},
+};
+
+const ENGINE_NAME: &str = concat!("zertz-engine ", env!("CARGO_PKG_VERSION"));
+const DEFAULT_HASH_SIZE_MB: usize = 16;
+const DEFAULT_DEPTH: u8 = 4;
+// Without `movestogo`, the remaining time is shared by this many moves.
+const DEFAULT_MOVES_TO_GO: u32 = 20;
+// Kept on the clock for the delay between the engine and the GUI.
+const MOVE_OVERHEAD: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone)]
+struct Options {
+    hash_size_mb: usize,
+    depth: u8,
+    move_time: Option<Duration>,
+    board_kind: BoardKind,
+    rules: Rules,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            hash_size_mb: DEFAULT_HASH_SIZE_MB,
+            depth: DEFAULT_DEPTH,
+            move_time: None,
+            board_kind: BoardKind::Rings61,
+            rules: Rules::default(),
+        }
+    }
+}
+
+/// State of a connection with a GUI. Every line is answered through `out`,
+/// which is shared with the thread of a running search.
+pub struct Session<W: Write + Send + 'static> {
+    out: Arc<Mutex<W>>,
+    options: Options,
+    game: Game,
+    // `None` while the engine is moved into the search thread.
+    engine: Option<Engine>,
+    search: Option<JoinHandle<Engine>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl<W: Write + Send + 'static> Session<W> {
+    pub fn new(out: W) -> Self {
+        let options = Options::default();
+        let engine = Engine::new(options.hash_size_mb);
+
+        Self {
+            out: Arc::new(Mutex::new(out)),
+            game: Game::with_rules(options.board_kind, options.rules),
+            options,
+            stop: engine.stop_handle(),
+            engine: Some(engine),
+            search: None,
+        }
+    }
+
+    /// Handle a line from the GUI. This returns `Ok(false)` after `quit`.
+    /// Errors are reported to the GUI, so only an IO error is returned.
+    pub fn handle_line(&mut self, line: &str) -> error::Result<bool> {
+        if line.trim().is_empty() {
+            return Ok(true);
+        }
+
+        let result = line
+            .parse::<Command>()
+            .and_then(|command| self.handle(command));
+        match result {
+            Ok(keep_going) => Ok(keep_going),
+            Err(ZertzEngineError::IOErr(err)) => Err(err.into()),
+            Err(err) => {
+                self.send(&format!("info string error {err}"))?;
+                Ok(true)
+            }
+        }
+    }
+
+    fn handle(&mut self, command: Command) -> error::Result<bool> {
+        // Only these are answered while the engine is thinking.
+        if !matches!(command, Command::IsReady | Command::Stop | Command::Quit) {
+            self.stop_search();
+        }
+
+        match command {
+            Command::Zei => self.zei()?,
+            Command::IsReady => self.send("readyok")?,
+            Command::SetOption { name, value } => self.set_option(&name, &value)?,
+            Command::NewGame => {
+                self.engine().clear();
+                self.game = Game::with_rules(self.options.board_kind, self.options.rules);
+            }
+            Command::Position { position, moves } => {
+                let mut game = match position {
+                    Position::StartPos => {
+                        Game::with_rules(self.options.board_kind, self.options.rules)
+                    }
+                    Position::Pos(position) => Game::from_position_string(&position)?,
+                };
+                for mv in moves {
+                    game.play_move(mv)?;
+                }
+                self.game = game;
+            }
+            Command::Go(params) => self.go(params)?,
+            Command::Stop => self.stop_search(),
+            Command::Print => {
+                let position = self.game.to_position_string();
+                self.send(&format!("info string position {position}"))?;
+            }
+            Command::Quit => {
+                self.stop_search();
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn zei(&mut self) -> error::Result<()> {
+        let default = Options::default();
+
+        self.send(&format!("id name {ENGINE_NAME}"))?;
+        self.send(&format!(
+            "option name Hash type spin default {} min 1 max 4096",
+            default.hash_size_mb
+        ))?;
+        self.send(&format!(
+            "option name Depth type spin default {} min 1 max 64",
+            default.depth
+        ))?;
+        self.send("option name MoveTime type spin default 0 min 0 max 3600000")?;
+        self.send(&format!(
+            "option name BoardKind type combo default {} var 37 var 40 var 43 var 44 var 48 var 61",
+            default.board_kind
+        ))?;
+        self.send(&format!(
+            "option name Rules type combo default {} var standard var blitz",
+            default.rules
+        ))?;
+        self.send("option name Book type string default <empty>")?;
+        self.send("zeiok")
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) -> error::Result<()> {
+        match name {
+            "Hash" => {
+                self.options.hash_size_mb = parse_value(name, value)?;
+                let hash_size_mb = self.options.hash_size_mb;
+                self.engine().set_hash_size(hash_size_mb);
+            }
+            "Depth" => self.options.depth = parse_value(name, value)?,
+            "MoveTime" => {
+                let millis = parse_value::<u64>(name, value)?;
+                self.options.move_time = (millis > 0).then(|| Duration::from_millis(millis));
+            }
+            "BoardKind" => self.options.board_kind = value.parse()?,
+            "Rules" => self.options.rules = value.parse()?,
+            "Book" => {
+                let book = match value {
+                    "<empty>" => None,
+                    path => Some(OpeningBook::load(fs::read_to_string(path)?)?),
+                };
+                self.engine().set_book(book, BookSelection::Weighted);
+            }
+            _ => return Err(ZertzEngineError::UnknownOption(name.to_string())),
+        }
+
+        Ok(())
+    }
+
+    fn go(&mut self, params: GoParams) -> error::Result<()> {
+        let limits = self.search_limits(&params);
+        self.stop_search();
+        let mut engine = self
+            .engine
+            .take()
+            .expect("the engine is back from the search");
+        let game = self.game.clone();
+        let out = Arc::clone(&self.out);
+
+        self.search = Some(thread::spawn(move || {
+            let result = engine.search(&game, limits, |info| {
+                // A closed output only means that the GUI is gone.
+                let _ = writeln!(
+                    out.lock().expect("output is poisoned"),
+                    "{}",
+                    info_line(info)
+                );
+            });
+
+            let mut out = out.lock().expect("output is poisoned");
+            if result.from_book {
+                let _ = writeln!(out, "info string book move");
+            }
+            let best_move = result
+                .best_move
+                .map_or("none".to_string(), |mv| mv.to_string());
+            let _ = writeln!(out, "bestmove {best_move}");
+            let _ = out.flush();
+
+            engine
+        }));
+
+        Ok(())
+    }
+
+    fn search_limits(&self, params: &GoParams) -> SearchLimits {
+        let side = match self.game.current_player() {
+            Player::Bob => 1,
+            _ => 0,
+        };
+
+        if params.infinite {
+            return SearchLimits::default();
+        }
+        if params.depth.is_some() || params.move_time.is_some() || params.nodes.is_some() {
+            return SearchLimits {
+                depth: params.depth,
+                move_time: params.move_time,
+                nodes: params.nodes,
+            };
+        }
+        if let Some(time) = params.time[side] {
+            let moves_to_go = params.moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+            let budget = time / moves_to_go + params.increment[side] / 2;
+            return SearchLimits {
+                move_time: Some(budget.min(time.saturating_sub(MOVE_OVERHEAD))),
+                ..SearchLimits::default()
+            };
+        }
+
+        SearchLimits {
+            depth: Some(self.options.depth),
+            move_time: self.options.move_time,
+            nodes: None,
+        }
+    }
+
+    /// Stop the running search, if any, and get the engine back.
+    fn stop_search(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.wait_for_search();
+    }
+
+    /// Let the running search, if any, finish and get the engine back.
+    pub fn wait_for_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            self.engine = Some(search.join().expect("the search thread panicked"));
+        }
+        self.stop.store(false, Ordering::Relaxed);
+    }
+
+    fn engine(&mut self) -> &mut Engine {
+        self.stop_search();
+        self.engine
+            .as_mut()
+            .expect("the engine is back from the search")
+    }
+
+    fn send(&self, line: &str) -> error::Result<()> {
+        let mut out = self.out.lock().expect("output is poisoned");
+        writeln!(out, "{line}")?;
+        out.flush()?;
+
+        Ok(())
+    }
+}
+
+fn info_line(info: &SearchInfo) -> String {
+    let score = match plies_to_end(info.score) {
+        Some(plies) if info.score > 0 => format!("win {plies}"),
+        Some(plies) => format!("loss {plies}"),
+        None => format!("cp {}", info.score),
+    };
+    let principal_line = info
+        .principal_line
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!(
+        "info depth {} score {} nodes {} time {} pv {}",
+        info.depth,
+        score,
+        info.nodes,
+        info.elapsed.as_millis(),
+        principal_line
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn run(lines: &[&str]) -> Vec<String> {
+        let buffer = SharedBuffer::default();
+        let mut session = Session::new(buffer.clone());
+        for line in lines {
+            session.handle_line(line).unwrap();
+        }
+        session.wait_for_search();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        output.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn handshake() {
+        let output = run(&["zei", "isready"]);
+        assert!(output[0].starts_with("id name zertz-engine"));
+        assert_eq!(output[output.len() - 2], "zeiok");
+        assert_eq!(output[output.len() - 1], "readyok");
+    }
+
+    #[test]
+    fn search_a_position() {
+        let output = run(&[
+            "setoption name BoardKind value 37",
+            "position startpos moves Wd4,a1",
+            "go depth 1",
+        ]);
+
+        assert!(output[0].starts_with("info depth 1 score "));
+        let best_move = output.last().unwrap().strip_prefix("bestmove ").unwrap();
+        let mut game = Game::new(BoardKind::Rings37);
+        game.play_move("Wd4,a1".parse().unwrap()).unwrap();
+        assert!(game.legal_moves().contains(&best_move.parse().unwrap()));
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        let output = run(&[
+            "position startpos moves Wz9",
+            "setoption name Color value red",
+        ]);
+        assert!(output
+            .iter()
+            .all(|line| line.starts_with("info string error")));
+        assert_eq!(output.len(), 2);
+    }
+
+    #[test]
+    fn other_commands_stop_the_search() {
+        let output = run(&["setoption name BoardKind value 37", "go infinite", "print"]);
+
+        let best_move = output
+            .iter()
+            .position(|line| line.starts_with("bestmove "))
+            .unwrap();
+        let position = output
+            .iter()
+            .position(|line| line.starts_with("info string position "))
+            .unwrap();
+        assert!(best_move < position);
+    }
+}
@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ZertzServerError {
+    #[error("{0}")]
+    IOErr(#[from] std::io::Error),
+    #[error("{0}")]
+    ZertzCoreErr(#[from] zertz_core::error::ZertzCoreError),
+}
+
+pub type Result<T> = std::result::Result<T, ZertzServerError>;
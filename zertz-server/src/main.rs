@@ -0,0 +1,43 @@
+mod error;
+mod server;
+
+use std::net::TcpListener;
+
+use clap::Parser;
+use zertz_core::{app::App, board::BoardKind, clock::TimeControl, game::Rules, net::DEFAULT_PORT};
+
+use server::Server;
+
+/// Host a game of ZERTZ for two players of `zertz-terminal --connect`, and
+/// any number of observers of `zertz-terminal --watch`.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    /// Address to listen on.
+    #[arg(short, long, default_value_t = format!("127.0.0.1:{DEFAULT_PORT}"))]
+    addr: String,
+    /// Number of rings of the board.
+    #[arg(short, long, default_value_t = BoardKind::Rings61)]
+    board_kind: BoardKind,
+    /// Either standard or blitz.
+    #[arg(short, long, default_value_t = Rules::Standard)]
+    rules: Rules,
+    /// Time of each player in seconds, like 300, 300+5 or 600/30x5.
+    #[arg(short, long)]
+    time: Option<TimeControl>,
+}
+
+fn main() -> error::Result<()> {
+    let cli = Cli::parse();
+
+    let listener = TcpListener::bind(&cli.addr)?;
+    println!("Listening on {}", listener.local_addr()?);
+
+    let mut app = App::with_rules(cli.board_kind, cli.rules);
+    app.set_time_control(cli.time);
+
+    let app = Server::new(app).serve(listener)?;
+    println!("Game over: {:?}", app.result());
+
+    Ok(())
+}
@@ -0,0 +1,501 @@
+use std::collections::{HashMap, HashSet};
+use std::io::BufReader;
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+use zertz_core::{
+    app::App,
+    error::ZertzCoreError,
+    game::{GameState, Move, Player},
+    net::{self, ClientMessage, GameSnapshot, ServerMessage},
+};
+
+use crate::error;
+
+type ClientId = usize;
+
+enum ServerEvent {
+    Connected(ClientId, TcpStream),
+    Message(ClientId, ClientMessage),
+    Invalid(ClientId, String),
+    Disconnected(ClientId),
+}
+
+#[derive(Debug, Default)]
+struct Seat {
+    name: String,
+    client: Option<ClientId>,
+}
+
+/// Owner of the authoritative game. Clients only send moves, and every
+/// change of the game is sent back to the players and observers. Observers
+/// get the moves one by one instead of the whole game.
+pub struct Server {
+    app: App,
+    seats: [Seat; 2],
+    clients: HashMap<ClientId, TcpStream>,
+    observers: HashSet<ClientId>,
+}
+
+impl Server {
+    pub fn new(app: App) -> Self {
+        Self {
+            app,
+            seats: Default::default(),
+            clients: HashMap::new(),
+            observers: HashSet::new(),
+        }
+    }
+
+    /// Serve clients of `listener` until the game is over, and return the
+    /// finished game.
+    pub fn serve(mut self, listener: TcpListener) -> error::Result<App> {
+        let (event_sender, event_receiver) = channel::<ServerEvent>();
+
+        thread::spawn(move || accept_clients(listener, event_sender));
+
+        loop {
+            // Wake up now and then to see whether a flag has fallen.
+            let event = match event_receiver.recv_timeout(Duration::from_millis(100)) {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) => {
+                    if self.app.check_flag() {
+                        self.broadcast_state();
+                        break;
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+
+            match event {
+                ServerEvent::Connected(id, stream) => {
+                    self.clients.insert(id, stream);
+                }
+                ServerEvent::Message(id, ClientMessage::Join { name }) => self.join(id, name),
+                ServerEvent::Message(id, ClientMessage::Watch) => self.watch(id),
+                ServerEvent::Message(id, ClientMessage::Play { mv }) => {
+                    let seat = self.seat_of(id);
+                    let result = match seat {
+                        None => Err("you do not have a seat".to_string()),
+                        Some(player) if player != self.app.game().current_player() => {
+                            Err("it is not your turn".to_string())
+                        }
+                        Some(_) => self.app.play_move(mv).map_err(|err| err.to_string()),
+                    };
+
+                    match result {
+                        Ok(()) => self.broadcast_move(mv),
+                        Err(reason) => self.send(id, &ServerMessage::Rejected { reason }),
+                    }
+                    if let GameState::GameEnd(_) = self.app.get_game_state() {
+                        break;
+                    }
+                }
+                ServerEvent::Message(
+                    id,
+                    message @ (ClientMessage::Resign
+                    | ClientMessage::OfferDraw
+                    | ClientMessage::AcceptDraw
+                    | ClientMessage::DeclineDraw),
+                ) => {
+                    let result = match self.seat_of(id) {
+                        None => Err("you do not have a seat".to_string()),
+                        Some(player) => match message {
+                            ClientMessage::Resign => self.app.resign(player),
+                            ClientMessage::OfferDraw => self.app.offer_draw(player),
+                            ClientMessage::AcceptDraw => self.app.accept_draw(player),
+                            _ => self.app.decline_draw(player),
+                        }
+                        .map_err(|err| err.to_string()),
+                    };
+
+                    match result {
+                        Ok(()) => self.broadcast_state(),
+                        Err(reason) => self.send(id, &ServerMessage::Rejected { reason }),
+                    }
+                    if let GameState::GameEnd(_) = self.app.get_game_state() {
+                        break;
+                    }
+                }
+                ServerEvent::Message(id, ClientMessage::Leave) | ServerEvent::Disconnected(id) => {
+                    self.leave(id)
+                }
+                ServerEvent::Invalid(id, reason) => {
+                    self.send(id, &ServerMessage::Rejected { reason })
+                }
+            }
+        }
+
+        Ok(self.app)
+    }
+
+    fn seat_of(&self, id: ClientId) -> Option<Player> {
+        self.seats
+            .iter()
+            .position(|seat| seat.client == Some(id))
+            .map(seat_to_player)
+    }
+
+    fn join(&mut self, id: ClientId, name: String) {
+        if self.seat_of(id).is_some() {
+            let reason = "you already have a seat".to_string();
+            return self.send(id, &ServerMessage::Rejected { reason });
+        }
+
+        self.observers.remove(&id);
+
+        // A player who comes back gets the same seat.
+        let idx = self
+            .seats
+            .iter()
+            .position(|seat| seat.client.is_none() && !name.is_empty() && seat.name == name)
+            .or_else(|| self.seats.iter().position(|seat| seat.name.is_empty()));
+        let Some(idx) = idx else {
+            let reason = "the game is full".to_string();
+            return self.send(id, &ServerMessage::Rejected { reason });
+        };
+
+        self.seats[idx] = Seat {
+            name: if name.is_empty() {
+                format!("{:?}", seat_to_player(idx))
+            } else {
+                name
+            },
+            client: Some(id),
+        };
+        // The clock starts once both players are there for the first time.
+        if self.app.moves().is_empty() && self.seats.iter().all(|seat| seat.client.is_some()) {
+            self.app.start_clock();
+        }
+        self.send(
+            id,
+            &ServerMessage::Welcome {
+                player: seat_to_player(idx),
+            },
+        );
+        self.broadcast_state();
+    }
+
+    fn watch(&mut self, id: ClientId) {
+        if self.seat_of(id).is_some() {
+            let reason = "a player cannot watch the game".to_string();
+            return self.send(id, &ServerMessage::Rejected { reason });
+        }
+
+        self.observers.insert(id);
+        let message = self.state_message();
+        self.send(id, &message);
+    }
+
+    fn leave(&mut self, id: ClientId) {
+        self.observers.remove(&id);
+        for seat in self.seats.iter_mut() {
+            if seat.client == Some(id) {
+                seat.client = None;
+            }
+        }
+        if let Some(stream) = self.clients.remove(&id) {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    fn state_message(&self) -> ServerMessage {
+        let players = self.seats.each_ref().map(|seat| seat.name.clone());
+        ServerMessage::State {
+            snapshot: Box::new(GameSnapshot::new(&self.app, players)),
+        }
+    }
+
+    // Seated players and observers. Other clients have not asked for the
+    // game, and only get answers to what they send.
+    fn audience(&self) -> Vec<ClientId> {
+        self.seats
+            .iter()
+            .filter_map(|seat| seat.client)
+            .chain(self.observers.iter().copied())
+            .collect()
+    }
+
+    fn broadcast_state(&mut self) {
+        let message = self.state_message();
+
+        for id in self.audience() {
+            self.send(id, &message);
+        }
+    }
+
+    fn broadcast_move(&mut self, mv: Move) {
+        let state = self.state_message();
+        let moved = ServerMessage::Moved { mv };
+
+        for id in self.audience() {
+            if self.observers.contains(&id) {
+                self.send(id, &moved);
+            } else {
+                self.send(id, &state);
+            }
+        }
+    }
+
+    // A client which cannot be written to is gone, and its reader thread
+    // reports the disconnection.
+    fn send(&mut self, id: ClientId, message: &ServerMessage) {
+        if let Some(stream) = self.clients.get_mut(&id) {
+            if net::send(stream, message).is_err() {
+                self.clients.remove(&id);
+            }
+        }
+    }
+}
+
+fn seat_to_player(idx: usize) -> Player {
+    if idx == 0 {
+        Player::Alice
+    } else {
+        Player::Bob
+    }
+}
+
+fn accept_clients(listener: TcpListener, event_sender: Sender<ServerEvent>) {
+    for (id, stream) in listener.incoming().enumerate() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let Ok(reader) = stream.try_clone() else {
+            continue;
+        };
+        if event_sender
+            .send(ServerEvent::Connected(id, stream))
+            .is_err()
+        {
+            break;
+        }
+
+        let event_sender = event_sender.clone();
+        thread::spawn(move || read_client(id, reader, event_sender));
+    }
+}
+
+fn read_client(id: ClientId, stream: TcpStream, event_sender: Sender<ServerEvent>) {
+    let mut reader = BufReader::new(stream);
+
+    loop {
+        let event = match net::receive::<ClientMessage>(&mut reader) {
+            Ok(Some(message)) => ServerEvent::Message(id, message),
+            Ok(None) | Err(ZertzCoreError::IOErr(_)) => break,
+            Err(err) => ServerEvent::Invalid(id, err.to_string()),
+        };
+        if event_sender.send(event).is_err() {
+            return;
+        }
+    }
+
+    let _ = event_sender.send(ServerEvent::Disconnected(id));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use zertz_core::{
+        app::{EndReason, GameResult},
+        board::BoardKind,
+    };
+
+    struct Client {
+        stream: TcpStream,
+        reader: BufReader<TcpStream>,
+    }
+
+    impl Client {
+        fn connect(addr: std::net::SocketAddr) -> Self {
+            let stream = TcpStream::connect(addr).unwrap();
+            let reader = BufReader::new(stream.try_clone().unwrap());
+            Self { stream, reader }
+        }
+
+        fn send(&mut self, message: ClientMessage) {
+            net::send(&mut self.stream, &message).unwrap();
+        }
+
+        fn receive(&mut self) -> ServerMessage {
+            net::receive(&mut self.reader).unwrap().unwrap()
+        }
+
+        fn join(&mut self, name: &str) -> Player {
+            self.send(ClientMessage::Join {
+                name: name.to_string(),
+            });
+            let ServerMessage::Welcome { player } = self.receive() else {
+                panic!("not welcomed");
+            };
+            player
+        }
+
+        fn receive_state(&mut self) -> GameSnapshot {
+            loop {
+                if let ServerMessage::State { snapshot } = self.receive() {
+                    return *snapshot;
+                }
+            }
+        }
+    }
+
+    fn start_server() -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = Server::new(App::new(BoardKind::Rings37));
+        thread::spawn(move || server.serve(listener));
+
+        addr
+    }
+
+    #[test]
+    fn moves_are_validated_and_broadcast() {
+        let addr = start_server();
+
+        let mut alice = Client::connect(addr);
+        assert_eq!(alice.join("alice"), Player::Alice);
+        alice.receive_state();
+
+        let mut bob = Client::connect(addr);
+        assert_eq!(bob.join("bob"), Player::Bob);
+        let snapshot = bob.receive_state();
+        assert_eq!(snapshot.players, ["alice".to_string(), "bob".to_string()]);
+        alice.receive_state();
+
+        let mv = snapshot.game.legal_moves()[0];
+        bob.send(ClientMessage::Play { mv });
+        assert!(matches!(bob.receive(), ServerMessage::Rejected { .. }));
+
+        alice.send(ClientMessage::Play { mv });
+        for client in [&mut alice, &mut bob] {
+            let snapshot = client.receive_state();
+            assert_eq!(snapshot.moves, vec![mv]);
+            assert_eq!(snapshot.game.current_player(), Player::Bob);
+        }
+
+        let mut third = Client::connect(addr);
+        third.send(ClientMessage::Join {
+            name: "carol".to_string(),
+        });
+        assert!(matches!(third.receive(), ServerMessage::Rejected { .. }));
+
+        third.stream.write_all(b"not json\n").unwrap();
+        assert!(matches!(third.receive(), ServerMessage::Rejected { .. }));
+    }
+
+    #[test]
+    fn only_players_and_observers_get_the_game() {
+        let addr = start_server();
+        let mut idle = Client::connect(addr);
+
+        let mut alice = Client::connect(addr);
+        alice.join("alice");
+        alice.receive_state();
+        let mut bob = Client::connect(addr);
+        bob.join("bob");
+        let snapshot = bob.receive_state();
+        alice.receive_state();
+        alice.send(ClientMessage::Play {
+            mv: snapshot.game.legal_moves()[0],
+        });
+        alice.receive_state();
+
+        // The first message the idle client gets answers its own.
+        idle.stream.write_all(b"not json\n").unwrap();
+        assert!(matches!(idle.receive(), ServerMessage::Rejected { .. }));
+    }
+
+    #[test]
+    fn seat_is_kept_for_a_returning_player() {
+        let addr = start_server();
+
+        let mut alice = Client::connect(addr);
+        alice.join("alice");
+        alice.send(ClientMessage::Leave);
+        // The server closes the connection once it has freed the seat.
+        while net::receive::<ServerMessage>(&mut alice.reader)
+            .unwrap()
+            .is_some()
+        {}
+
+        let mut bob = Client::connect(addr);
+        assert_eq!(bob.join("bob"), Player::Bob);
+
+        let mut alice = Client::connect(addr);
+        assert_eq!(alice.join("alice"), Player::Alice);
+    }
+
+    #[test]
+    fn draw_by_agreement() {
+        let addr = start_server();
+
+        let mut alice = Client::connect(addr);
+        alice.join("alice");
+        alice.receive_state();
+        let mut bob = Client::connect(addr);
+        bob.join("bob");
+        bob.receive_state();
+        alice.receive_state();
+
+        bob.send(ClientMessage::AcceptDraw);
+        assert!(matches!(bob.receive(), ServerMessage::Rejected { .. }));
+
+        alice.send(ClientMessage::OfferDraw);
+        assert_eq!(bob.receive_state().draw_offer, Some(Player::Alice));
+        alice.receive_state();
+
+        bob.send(ClientMessage::AcceptDraw);
+        let app = bob.receive_state().into_app();
+        assert_eq!(
+            app.result(),
+            Some(GameResult {
+                winner: None,
+                reason: EndReason::Agreement,
+            })
+        );
+    }
+
+    #[test]
+    fn observers_follow_the_moves() {
+        let addr = start_server();
+
+        let mut alice = Client::connect(addr);
+        alice.join("alice");
+        alice.receive_state();
+        let mut bob = Client::connect(addr);
+        bob.join("bob");
+        let snapshot = bob.receive_state();
+        alice.receive_state();
+
+        let first = snapshot.game.legal_moves()[0];
+        alice.send(ClientMessage::Play { mv: first });
+        let snapshot = alice.receive_state();
+        bob.receive_state();
+
+        // An observer joining in the middle of the game gets all of it.
+        let mut observer = Client::connect(addr);
+        observer.send(ClientMessage::Watch);
+        let mut app = observer.receive_state().into_app();
+        assert_eq!(app.moves(), &[first]);
+
+        observer.send(ClientMessage::Play {
+            mv: snapshot.game.legal_moves()[0],
+        });
+        assert!(matches!(observer.receive(), ServerMessage::Rejected { .. }));
+
+        let second = snapshot.game.legal_moves()[0];
+        bob.send(ClientMessage::Play { mv: second });
+        let ServerMessage::Moved { mv } = observer.receive() else {
+            panic!("not a move");
+        };
+        assert_eq!(mv, second);
+        app.play_move(mv).unwrap();
+        assert_eq!(app.game().current_player(), Player::Alice);
+    }
+}
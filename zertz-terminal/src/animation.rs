@@ -0,0 +1,131 @@
+use std::time::{Duration, Instant};
+
+use zertz_core::{
+    app::MoveChange,
+    board::Ring,
+    coordinate::CoordinateIter,
+    game::{CatchableMove, Move},
+};
+
+use crate::renderer::game_board::{GameBoard, VisualRing};
+
+const STEP: Duration = Duration::from_millis(150);
+// The jumping marble lands in two steps, and the caught one flashes in four.
+const JUMP_STEPS: usize = 6;
+// The removed ring fades in two steps, and the isolated rings flash in four.
+const FADE_STEPS: usize = 2;
+const FLASH_STEPS: usize = 4;
+
+/// The last move shown step by step, so that it is seen what happened.
+pub struct Animation {
+    change: MoveChange,
+    started: Instant,
+}
+
+impl Animation {
+    pub fn new(change: MoveChange) -> Self {
+        Self {
+            change,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn is_over(&self, now: Instant) -> bool {
+        self.step(now) >= self.steps()
+    }
+
+    /// `board` as it is shown at `now`, with the position before the move.
+    /// This is `None` once the animation is over.
+    pub fn frame(&self, board: GameBoard, now: Instant) -> Option<GameBoard> {
+        let step = self.step(now);
+        if step >= self.steps() {
+            return None;
+        }
+
+        let mut board = board;
+        board.update(&self.change.before);
+        for coord in CoordinateIter::new() {
+            board[coord] = VisualRing {
+                kind: board[coord].kind,
+                ..VisualRing::default()
+            };
+        }
+        match self.change.mv {
+            Move::Catch(catch) => self.jump(&mut board, catch, step),
+            Move::Put {
+                put_coord,
+                remove_coord,
+                marble,
+            } => {
+                board[put_coord].kind = Ring::Occupied(marble);
+                match (step, remove_coord) {
+                    (0, _) => board[put_coord].selected = true,
+                    (1, Some(coord)) => board[coord].faded = true,
+                    (_, Some(coord)) => board[coord].kind = Ring::Empty,
+                    _ => {}
+                }
+                // The isolated rings flash before they are gone.
+                if step > self.fade_steps() {
+                    for &coord in &self.change.isolated {
+                        board[coord].focused = !step.is_multiple_of(2);
+                    }
+                }
+            }
+        }
+
+        Some(board)
+    }
+
+    // The marble goes over the caught one, which flashes once it is jumped.
+    fn jump(&self, board: &mut GameBoard, catch: CatchableMove, step: usize) {
+        let (start, over, land) = (
+            catch.start_coord(),
+            catch.catched_coord(),
+            catch.marble_land_coord(),
+        );
+        let (jumper, caught) = (board[start].kind, board[over].kind);
+
+        match step {
+            0 => board[start].selected = true,
+            1 => {
+                board[start].kind = Ring::Vacant;
+                board[over].kind = jumper;
+                board[over].selected = true;
+            }
+            _ => {
+                board[start].kind = Ring::Vacant;
+                board[land].kind = jumper;
+                board[land].selected = true;
+                board[over].kind = if step.is_multiple_of(2) {
+                    caught
+                } else {
+                    Ring::Vacant
+                };
+                board[over].focused = true;
+            }
+        }
+    }
+
+    fn step(&self, now: Instant) -> usize {
+        (now.saturating_duration_since(self.started).as_millis() / STEP.as_millis()) as usize
+    }
+
+    // Steps until the removed ring is gone.
+    fn fade_steps(&self) -> usize {
+        match self.change.mv {
+            Move::Put {
+                remove_coord: Some(_),
+                ..
+            } => FADE_STEPS,
+            _ => 0,
+        }
+    }
+
+    fn steps(&self) -> usize {
+        match self.change.mv {
+            Move::Catch(_) => JUMP_STEPS,
+            Move::Put { .. } if self.change.isolated.is_empty() => 1 + self.fade_steps(),
+            Move::Put { .. } => 1 + self.fade_steps() + FLASH_STEPS,
+        }
+    }
+}
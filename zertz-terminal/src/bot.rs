@@ -0,0 +1,74 @@
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+
+use zertz_core::{
+    game::{Game, Move, Player},
+    search::engine::{Engine, SearchLimits},
+};
+
+const HASH_SIZE_MB: usize = 16;
+
+/// A computer player, which thinks on its own thread so the board and the
+/// clocks are still drawn meanwhile.
+pub struct Bot {
+    player: Player,
+    limits: SearchLimits,
+    // The engine is lent to the thread while it is thinking.
+    engine: Option<Engine>,
+    thinking: Option<Receiver<(Engine, Option<Move>)>>,
+}
+
+impl Bot {
+    pub fn new(player: Player, limits: SearchLimits) -> Self {
+        Self {
+            player,
+            limits,
+            engine: Some(Engine::new(HASH_SIZE_MB)),
+            thinking: None,
+        }
+    }
+
+    #[inline]
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    #[inline]
+    pub fn is_thinking(&self) -> bool {
+        self.thinking.is_some()
+    }
+
+    /// Start looking for a move in `game`, unless it is already thinking.
+    pub fn think(&mut self, game: &Game) {
+        let Some(mut engine) = self.engine.take() else {
+            return;
+        };
+
+        let game = game.clone();
+        let limits = self.limits;
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            let best_move = engine
+                .search(&game, limits, |_| {})
+                .best_move
+                .or_else(|| game.legal_moves().first().copied());
+            let _ = sender.send((engine, best_move));
+        });
+        self.thinking = Some(receiver);
+    }
+
+    /// The move found by the last [`Bot::think`], once it is done.
+    pub fn try_move(&mut self) -> Option<Move> {
+        let receiver = self.thinking.as_ref()?;
+        let (engine, best_move) = match receiver.try_recv() {
+            Ok(found) => found,
+            Err(TryRecvError::Empty) => return None,
+            // The search panicked, so start again with a new engine.
+            Err(TryRecvError::Disconnected) => (Engine::new(HASH_SIZE_MB), None),
+        };
+
+        self.engine = Some(engine);
+        self.thinking = None;
+        best_move
+    }
+}
@@ -7,8 +7,8 @@ pub enum ZertzTerminalError {
     IOErr(#[from] std::io::Error),
     #[error("{0}")]
     ZertzCoreErr(#[from] zertz_core::error::ZertzCoreError),
-    #[error("current terminal is too small to play the game")]
-    InappropriateTerminalSize,
+    #[error("invalid theme file: {0}")]
+    InvalidTheme(#[from] serde_json::Error),
     #[error("cannot get a proper key event")]
     CannotGetKeyEvent,
     #[error("cannot get a proper mouse event")]
@@ -19,6 +19,10 @@ pub enum ZertzTerminalError {
     UnexpectedGameState(zertz_core::game::GameState),
     #[error("Game is unexpectevely paniced.\n Reason: {0}")]
     UnexpectedPanic(String),
+    #[error("the server refused to let us join. Reason: {0}")]
+    ConnectionRejected(String),
+    #[error("the connection to the server is closed")]
+    ConnectionClosed,
     #[error("Not yet implemented.\n\tReason:{0}")]
     Todo(String),
 }
@@ -1,26 +1,239 @@
 #[macro_use]
 mod error;
 
+mod animation;
+mod bot;
 mod coordinate;
+mod menu;
 mod play_handler;
+mod remote;
 mod renderer;
+mod saves;
+mod theme;
 
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{channel, RecvTimeoutError};
 use std::thread;
-use std::time;
+use std::time::{self, Duration};
 
+use clap::{Parser, ValueEnum};
 use crossterm::event::Event;
 use zertz_core::app::App;
 use zertz_core::board::BoardKind;
+use zertz_core::clock::TimeControl;
+use zertz_core::game::{Player, Rules};
+use zertz_core::record::GameRecord;
+use zertz_core::search::engine::SearchLimits;
 
 use play_handler::PlayHandler;
-use renderer::{RenderData, Renderer};
+use remote::Remote;
+use renderer::{Charset, RenderData, Renderer};
+use theme::{Theme, ThemePreset};
+
+const DEFAULT_MOVE_TIME_MS: u64 = 1000;
+const DEFAULT_REPLAY_INTERVAL_MS: u64 = 1000;
+// A game on a server is set up by the server.
+const LOCAL_ARGS: [&str; 11] = [
+    "board_kind",
+    "rules",
+    "time",
+    "alice",
+    "bob",
+    "bot",
+    "load",
+    "autosave",
+    "saves_dir",
+    "no_takebacks",
+    "move_time",
+];
+
+/// Play ZERTZ in the terminal, against another person, the computer or
+/// someone on a `zertz-server`.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    /// Number of rings of the board.
+    #[arg(short, long, default_value_t = BoardKind::Rings61, conflicts_with = "load")]
+    board_kind: BoardKind,
+    /// Either standard or blitz.
+    #[arg(short, long, default_value_t = Rules::Standard, conflicts_with = "load")]
+    rules: Rules,
+    /// Time of each player in seconds, like 300, 300+5 or 600/30x5.
+    #[arg(short, long)]
+    time: Option<TimeControl>,
+    /// Name of Alice, who moves first.
+    #[arg(long)]
+    alice: Option<String>,
+    /// Name of Bob.
+    #[arg(long)]
+    bob: Option<String>,
+    /// Side played by the computer.
+    #[arg(long, value_enum)]
+    bot: Option<Side>,
+    /// Maximum search depth of the computer in plies.
+    #[arg(long)]
+    depth: Option<u8>,
+    /// Maximum thinking time of the computer for a move, in milliseconds.
+    /// This is one second unless a depth is given.
+    #[arg(long)]
+    move_time: Option<u64>,
+    /// Saved game or game record to go on with.
+    #[arg(short, long)]
+    load: Option<PathBuf>,
+    /// Save the game to this file after every move.
+    #[arg(short, long)]
+    autosave: Option<PathBuf>,
+    /// Forbid taking moves back, as in a rated game.
+    #[arg(long)]
+    no_takebacks: bool,
+    /// Change the board at once, without showing how each move went.
+    #[arg(long)]
+    no_animations: bool,
+    /// Directory of the games saved with Ctrl+S and loaded with Ctrl+O. An
+    /// unfinished game is saved there as `autosave` on quitting.
+    #[arg(short, long, default_value = "zertz-saves")]
+    saves_dir: PathBuf,
+    /// Play on a `zertz-server` at this address instead of locally.
+    #[arg(long, conflicts_with_all = LOCAL_ARGS)]
+    connect: Option<String>,
+    /// Follow the game of a `zertz-server` at this address without playing.
+    #[arg(long, conflicts_with_all = LOCAL_ARGS, conflicts_with = "connect")]
+    watch: Option<String>,
+    /// Step through a saved game or game record instead of playing.
+    #[arg(long, conflicts_with_all = LOCAL_ARGS, conflicts_with_all = ["connect", "watch"])]
+    replay: Option<PathBuf>,
+    /// Time between two moves of a replay which plays by itself, in
+    /// milliseconds.
+    #[arg(long, default_value_t = DEFAULT_REPLAY_INTERVAL_MS)]
+    replay_interval: u64,
+    /// Name sent to the server by `--connect`.
+    #[arg(long, default_value = "", requires = "connect")]
+    name: String,
+    /// Colors and glyphs of the board.
+    #[arg(long, value_enum, default_value_t = ThemePreset::Dark)]
+    theme: ThemePreset,
+    /// JSON file of a theme, in place of `--theme`.
+    #[arg(long, conflicts_with = "theme")]
+    theme_file: Option<PathBuf>,
+    /// Characters used for frames, rings and marbles. With ascii, marbles
+    /// are shown as W, G and B.
+    #[arg(long, value_enum, default_value_t = Charset::Auto)]
+    charset: Charset,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Side {
+    Alice,
+    Bob,
+}
+
+impl From<Side> for Player {
+    fn from(side: Side) -> Self {
+        match side {
+            Side::Alice => Player::Alice,
+            Side::Bob => Player::Bob,
+        }
+    }
+}
+
+// Either a game saved with its history, or a record of the moves.
+fn load_app(path: &Path) -> error::Result<App> {
+    let json = fs::read_to_string(path)?;
+
+    match App::load(&json) {
+        Ok(app) => Ok(app),
+        Err(_) => Ok(App::from_record(&GameRecord::load(&json)?)?),
+    }
+}
+
+// The moves of a saved game or a game record, with the comments of a record.
+fn load_record(path: &Path) -> error::Result<GameRecord> {
+    let json = fs::read_to_string(path)?;
+
+    match App::load(&json) {
+        Ok(app) => Ok(app.to_record()),
+        Err(_) => Ok(GameRecord::load(&json)?),
+    }
+}
+
+fn local_app(cli: &Cli) -> error::Result<App> {
+    let mut app = match &cli.load {
+        Some(path) => load_app(path)?,
+        None => App::with_rules(cli.board_kind, cli.rules),
+    };
+    if cli.load.is_none() || cli.time.is_some() {
+        app.set_time_control(cli.time);
+    }
+
+    let mut players = app.players().clone();
+    if let Some(side) = cli.bot {
+        let name = &mut players[usize::from(Player::from(side))];
+        if name.is_empty() {
+            *name = "Computer".to_string();
+        }
+    }
+    for (name, given) in players.iter_mut().zip([&cli.alice, &cli.bob]) {
+        if let Some(given) = given {
+            *name = given.clone();
+        }
+    }
+    app.set_players(players);
+
+    Ok(app)
+}
 
 fn main() -> error::Result<()> {
-    let (mut renderer, center, origin) = Renderer::new()?;
+    let cli = Cli::parse();
 
-    let (play_handler, init_render_data) =
-        PlayHandler::new(App::new(BoardKind::Rings61), center, origin);
+    let theme = match &cli.theme_file {
+        Some(path) => Theme::load(path)?,
+        None => cli.theme.theme(),
+    };
+    let record = cli.replay.as_deref().map(load_record).transpose()?;
+    // Connect before entering the raw mode, so errors are printed as usual.
+    let (app, remote) = match (&cli.connect, &cli.watch) {
+        (Some(addr), _) => {
+            let (remote, app) = Remote::connect(addr, &cli.name)?;
+            (app, Some(remote))
+        }
+        (None, Some(addr)) => {
+            let (remote, app) = Remote::watch(addr)?;
+            (app, Some(remote))
+        }
+        (None, None) => match &record {
+            Some(record) => (App::from_record(record)?, None),
+            None => (local_app(&cli)?, None),
+        },
+    };
+    // The computer may be chosen for either side in the menu as well.
+    let limits = SearchLimits {
+        depth: cli.depth,
+        move_time: match (cli.depth, cli.move_time) {
+            (Some(_), None) => None,
+            (_, move_time) => Some(Duration::from_millis(
+                move_time.unwrap_or(DEFAULT_MOVE_TIME_MS),
+            )),
+        },
+        nodes: None,
+    };
+    let bots = cli.bot.into_iter().map(Player::from).collect::<Vec<_>>();
+
+    let (mut renderer, layout) = Renderer::new()?;
+    renderer.set_ascii(cli.charset.is_ascii());
+
+    let (mut play_handler, mut init_render_data) = PlayHandler::new(app, remote, layout);
+    play_handler.set_search_limits(limits);
+    play_handler.set_bots(&bots);
+    play_handler.set_theme(theme);
+    play_handler.set_autosave(cli.autosave);
+    play_handler.set_saves_dir(cli.saves_dir);
+    play_handler.set_takebacks(!cli.no_takebacks);
+    play_handler.set_animations(!cli.no_animations);
+    play_handler.set_replay_interval(Duration::from_millis(cli.replay_interval));
+    if let Some(record) = record {
+        init_render_data = play_handler.start_replay(record.comments)?;
+    }
 
     let (render_data_sender, render_data_receiver) = channel::<Option<RenderData>>();
     let (event_sender, event_receiver) = channel::<Event>();
@@ -33,11 +246,7 @@ fn main() -> error::Result<()> {
         renderer.enable_raw_mode()?;
 
         // Power on the renderer
-        let mut prev_render_data = Some(init_render_data.clone());
-        let event = renderer.render(&init_render_data)?;
-        event_sender
-            .send(event)
-            .expect("send failed. (event_sender)");
+        let mut prev_render_data = Some(init_render_data);
 
         loop {
             let render_data = match render_data_receiver.recv_timeout(delta) {
@@ -51,9 +260,10 @@ fn main() -> error::Result<()> {
                 }
             };
             if let Some(render_data) = render_data {
-                let event = renderer.render(render_data)?;
-                if event_sender.send(event).is_err() {
-                    break;
+                if let Some(event) = renderer.render(render_data)? {
+                    if event_sender.send(event).is_err() {
+                        break;
+                    }
                 }
             } else {
                 break;
@@ -72,10 +282,18 @@ fn main() -> error::Result<()> {
         let mut play_handler = play_handler;
 
         loop {
-            let event = event_receiver
-                .recv()
-                .expect("receive failed (event_receiver)");
-            let render_data = play_handler.run_game(event)?;
+            // Without any input, look for messages of the server.
+            let render_data = match event_receiver.recv_timeout(delta) {
+                Ok(event) => play_handler.run_game(event)?,
+                Err(RecvTimeoutError::Timeout) => {
+                    let render_data = play_handler.tick()?;
+                    if render_data.is_none() && !play_handler.is_game_end() {
+                        continue;
+                    }
+                    render_data
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
             exit_game = render_data.is_none() || play_handler.is_game_end();
             render_data_sender
                 .send(render_data)
@@ -86,7 +304,7 @@ fn main() -> error::Result<()> {
             }
         }
 
-        Ok(())
+        play_handler.save_on_quit()
     });
 
     play_handler_thread.join().unwrap()?;
@@ -0,0 +1,380 @@
+use zertz_core::{board::BoardKind, game::Player, game::Rules};
+
+use crate::renderer::Dialog;
+
+const BOARD_KINDS: [BoardKind; 6] = [
+    BoardKind::Rings37,
+    BoardKind::Rings40,
+    BoardKind::Rings43,
+    BoardKind::Rings44,
+    BoardKind::Rings48,
+    BoardKind::Rings61,
+];
+const RULES: [Rules; 2] = [Rules::Standard, Rules::Blitz];
+// Labels are padded to this, so that the values are lined up.
+const LABEL_WIDTH: usize = 12;
+const HELP_TEXT: [&str; 9] = [
+    "Each turn, put a marble of any color on a vacant",
+    "ring, then remove a free ring from the edge. A",
+    "marble which can jump over another must do so,",
+    "and catches it. Marbles cut off from the board",
+    "are caught too. Whoever catches enough of one",
+    "color, or of each, wins.",
+    "",
+    "u takes back, Ctrl+R plays again, [ shows past",
+    "moves, Ctrl+S saves, Ctrl+O loads, q quits.",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuPage {
+    Main,
+    NewGame,
+    Settings,
+    Help,
+}
+
+// A line of the menu which can be chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Item {
+    Play,
+    NewGame,
+    Continue,
+    Load,
+    Replay,
+    Settings,
+    Help,
+    Quit,
+    BoardKind,
+    Rules,
+    Side(Player),
+    Start,
+    Theme,
+    Animations,
+    Takebacks,
+    Back,
+}
+
+impl MenuPage {
+    fn items(self) -> &'static [Item] {
+        match self {
+            Self::Main => &[
+                Item::Play,
+                Item::NewGame,
+                Item::Continue,
+                Item::Load,
+                Item::Replay,
+                Item::Settings,
+                Item::Help,
+                Item::Quit,
+            ],
+            Self::NewGame => &[
+                Item::BoardKind,
+                Item::Rules,
+                Item::Side(Player::Alice),
+                Item::Side(Player::Bob),
+                Item::Start,
+                Item::Back,
+            ],
+            Self::Settings => &[Item::Theme, Item::Animations, Item::Takebacks, Item::Back],
+            Self::Help => &[Item::Back],
+        }
+    }
+
+    // Lines shown above the items, which cannot be chosen.
+    fn text(self) -> &'static [&'static str] {
+        match self {
+            Self::Help => &HELP_TEXT,
+            _ => &[],
+        }
+    }
+}
+
+/// What the play handler does for the item chosen in the menu.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuAction {
+    /// The game set up from the command line.
+    Play,
+    /// A new game, where `computers` tells which sides the computer plays.
+    NewGame {
+        board_kind: BoardKind,
+        rules: Rules,
+        computers: [bool; 2],
+    },
+    /// The game left unfinished last time.
+    Continue,
+    Load,
+    Replay,
+    /// The next theme, or the one before when `forward` is false.
+    CycleTheme {
+        forward: bool,
+    },
+    ToggleAnimations,
+    ToggleTakebacks,
+    Quit,
+}
+
+/// Settings shown in the menu, which are kept by the play handler.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub theme: String,
+    pub animations: bool,
+    pub takebacks: bool,
+}
+
+/// The menu shown before a game is played.
+#[derive(Debug, Clone)]
+pub struct Menu {
+    page: MenuPage,
+    selected: usize,
+    board_kind: BoardKind,
+    rules: Rules,
+    computers: [bool; 2],
+}
+
+impl Menu {
+    /// A menu whose new game is set up like the game of the command line.
+    pub fn new(board_kind: BoardKind, rules: Rules, computers: [bool; 2]) -> Self {
+        Self {
+            page: MenuPage::Main,
+            selected: 0,
+            board_kind,
+            rules,
+            computers,
+        }
+    }
+
+    /// The page shown, as a dialog over the board.
+    pub fn dialog(&self, settings: &Settings) -> Dialog {
+        let on_off = |on: bool| if on { "on" } else { "off" };
+        let human_computer = |computer: bool| if computer { "computer" } else { "human" };
+        let value = |label: &str, value: &str| format!("{label:<LABEL_WIDTH$}< {value} >");
+
+        let text = self.page.text();
+        let items = self.page.items().iter().map(|item| match item {
+            Item::Play => "Play".to_string(),
+            Item::NewGame => "New game...".to_string(),
+            Item::Continue => "Continue the last game".to_string(),
+            Item::Load => "Load a game...".to_string(),
+            Item::Replay => "Replay a game...".to_string(),
+            Item::Settings => "Settings...".to_string(),
+            Item::Help => "Help".to_string(),
+            Item::Quit => "Quit".to_string(),
+            Item::BoardKind => value("Board", &format!("{} rings", self.board_kind)),
+            Item::Rules => value("Rules", &self.rules.to_string()),
+            Item::Side(player) => value(
+                &format!("{player:?}"),
+                human_computer(self.computers[usize::from(*player)]),
+            ),
+            Item::Start => "Start".to_string(),
+            Item::Theme => value("Theme", &settings.theme),
+            Item::Animations => value("Animations", on_off(settings.animations)),
+            Item::Takebacks => value("Takebacks", on_off(settings.takebacks)),
+            Item::Back => "Back".to_string(),
+        });
+
+        Dialog::Menu {
+            title: match self.page {
+                MenuPage::Main => "[ Zertz ]",
+                MenuPage::NewGame => "[ New Game ]",
+                MenuPage::Settings => "[ Settings ]",
+                MenuPage::Help => "[ Help ]",
+            }
+            .to_string(),
+            lines: text
+                .iter()
+                .map(|line| line.to_string())
+                .chain(items)
+                .collect(),
+            selected: text.len() + self.selected,
+        }
+    }
+
+    pub fn up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn down(&mut self) {
+        self.selected = (self.selected + 1).min(self.page.items().len() - 1);
+    }
+
+    /// Choose the item on `line` of the page, as clicked. Lines which are
+    /// not items do nothing.
+    pub fn click(&mut self, line: usize) -> Option<MenuAction> {
+        let idx = line.checked_sub(self.page.text().len())?;
+        if idx >= self.page.items().len() {
+            return None;
+        }
+        self.selected = idx;
+        self.choose(true)
+    }
+
+    /// Choose the highlighted item, or change its value to the next one. A
+    /// value goes back to the one before when `forward` is false, which does
+    /// nothing on other items.
+    pub fn choose(&mut self, forward: bool) -> Option<MenuAction> {
+        let item = self.page.items()[self.selected];
+        let open = |menu: &mut Self, page: MenuPage| {
+            menu.page = page;
+            menu.selected = 0;
+            None
+        };
+
+        match item {
+            Item::BoardKind => self.board_kind = cycle(&BOARD_KINDS, self.board_kind, forward),
+            Item::Rules => self.rules = cycle(&RULES, self.rules, forward),
+            Item::Side(player) => {
+                let computer = &mut self.computers[usize::from(player)];
+                *computer = !*computer;
+            }
+            Item::Theme => return Some(MenuAction::CycleTheme { forward }),
+            Item::Animations => return Some(MenuAction::ToggleAnimations),
+            Item::Takebacks => return Some(MenuAction::ToggleTakebacks),
+            _ if !forward => {}
+            Item::NewGame => return open(self, MenuPage::NewGame),
+            Item::Settings => return open(self, MenuPage::Settings),
+            Item::Help => return open(self, MenuPage::Help),
+            Item::Back => self.back(),
+            Item::Play => return Some(MenuAction::Play),
+            Item::Continue => return Some(MenuAction::Continue),
+            Item::Load => return Some(MenuAction::Load),
+            Item::Replay => return Some(MenuAction::Replay),
+            Item::Quit => return Some(MenuAction::Quit),
+            Item::Start => {
+                return Some(MenuAction::NewGame {
+                    board_kind: self.board_kind,
+                    rules: self.rules,
+                    computers: self.computers,
+                })
+            }
+        }
+
+        None
+    }
+
+    /// Go back to the main page, on the item of the page left.
+    pub fn back(&mut self) {
+        let item = match self.page {
+            MenuPage::Main => return,
+            MenuPage::NewGame => Item::NewGame,
+            MenuPage::Settings => Item::Settings,
+            MenuPage::Help => Item::Help,
+        };
+        self.page = MenuPage::Main;
+        self.selected = MenuPage::Main
+            .items()
+            .iter()
+            .position(|&main_item| main_item == item)
+            .unwrap_or_default();
+    }
+}
+
+/// The item after `current` in `items`, or the one before it when `forward`
+/// is false, going round at either end.
+pub fn cycle<T: Copy + PartialEq>(items: &[T], current: T, forward: bool) -> T {
+    let len = items.len();
+    let idx = items.iter().position(|&item| item == current);
+    let next = match (idx, forward) {
+        (Some(idx), true) => (idx + 1) % len,
+        (Some(idx), false) => (idx + len - 1) % len,
+        (None, _) => 0,
+    };
+
+    items[next]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn menu() -> Menu {
+        Menu::new(BoardKind::Rings37, Rules::Standard, [false, true])
+    }
+
+    #[test]
+    fn pages_open_and_go_back() {
+        let mut menu = menu();
+        menu.up();
+        assert_eq!(menu.selected, 0);
+        for _ in 0..20 {
+            menu.down();
+        }
+        assert_eq!(menu.selected, MenuPage::Main.items().len() - 1);
+
+        menu.selected = 1;
+        assert_eq!(menu.choose(true), None);
+        assert_eq!(menu.page, MenuPage::NewGame);
+        assert_eq!(menu.selected, 0);
+
+        // Values change on the new game page, and the game starts with them.
+        assert_eq!(menu.choose(false), None);
+        assert_eq!(menu.board_kind, BoardKind::Rings61);
+        menu.down();
+        menu.choose(true);
+        menu.down();
+        menu.choose(true);
+        menu.down();
+        menu.down();
+        assert_eq!(
+            menu.choose(true),
+            Some(MenuAction::NewGame {
+                board_kind: BoardKind::Rings61,
+                rules: Rules::Blitz,
+                computers: [true, true],
+            })
+        );
+
+        // Going back lands on the item of the page left.
+        menu.back();
+        assert_eq!(menu.page, MenuPage::Main);
+        assert_eq!(menu.selected, 1);
+        menu.back();
+        assert_eq!(menu.page, MenuPage::Main);
+    }
+
+    #[test]
+    fn backward_does_not_open_pages() {
+        let mut menu = menu();
+        menu.selected = 1;
+        assert_eq!(menu.choose(false), None);
+        assert_eq!(menu.page, MenuPage::Main);
+    }
+
+    #[test]
+    fn click_only_items() {
+        let mut menu = menu();
+        menu.selected = MenuPage::Main
+            .items()
+            .iter()
+            .position(|&item| item == Item::Help)
+            .unwrap();
+        menu.choose(true);
+        assert_eq!(menu.page, MenuPage::Help);
+
+        // The help text is above the only item, which is Back.
+        assert_eq!(menu.click(0), None);
+        assert_eq!(menu.page, MenuPage::Help);
+        assert_eq!(menu.click(HELP_TEXT.len() + 1), None);
+        assert_eq!(menu.page, MenuPage::Help);
+        assert_eq!(menu.click(HELP_TEXT.len()), None);
+        assert_eq!(menu.page, MenuPage::Main);
+
+        assert_eq!(menu.click(0), Some(MenuAction::Play));
+        assert_eq!(menu.click(MenuPage::Main.items().len()), None);
+    }
+
+    #[test]
+    fn cycle_goes_round() {
+        assert_eq!(cycle(&RULES, Rules::Blitz, true), Rules::Standard);
+        assert_eq!(cycle(&RULES, Rules::Standard, false), Rules::Blitz);
+        assert_eq!(
+            cycle(&BOARD_KINDS, BoardKind::Rings37, true),
+            BoardKind::Rings40
+        );
+        assert_eq!(
+            cycle(&BOARD_KINDS, BoardKind::Rings37, false),
+            BoardKind::Rings61
+        );
+        // A value which is not among the items starts over from the first.
+        assert_eq!(cycle(&[1, 2, 3], 7, false), 1);
+    }
+}
@@ -1,17 +1,67 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use clap::ValueEnum;
 use crossterm::event::{
     Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
 };
-use zertz_core::coordinate::CoordinateIter;
+use zertz_core::coordinate::{Coordinate as CoreCoordinate, CoordinateIter, Direction};
 use zertz_core::error::ZertzCoreError;
 use zertz_core::{
     app::{App, GameInputData, GameOutputData},
-    board::Marble,
-    game::GameState,
+    board::{Marble, Ring},
+    clock::Clock,
+    game::{CatchableMove, Game, GameState, Move, Player},
+    net::ServerMessage,
+    notation,
+    record::GameRecord,
+    search::engine::SearchLimits,
 };
 
+use crate::animation::Animation;
+use crate::bot::Bot;
 use crate::coordinate::Coordinate;
 use crate::error::{self, ZertzTerminalError};
-use crate::renderer::{game_board::GameBoard, RenderData, RendererState};
+use crate::menu::{self, Menu, MenuAction, Settings};
+use crate::remote::Remote;
+use crate::renderer::{
+    self, game_board::GameBoard, Dialog, GameSummary, Layout, MoveHistory, RenderData,
+    RendererState,
+};
+use crate::saves::{self, SaveEntry};
+use crate::theme::{Theme, ThemePreset};
+
+// A past position, which is shown instead of the game.
+struct Preview {
+    /// Number of moves played to get there.
+    shown: usize,
+    game_board: GameBoard,
+    // Explanation of the game, which comes back after the preview.
+    explanation: (String, String),
+}
+
+// A finished game, which is stepped through instead of played.
+struct Replay {
+    /// Number of moves played to the position shown.
+    shown: usize,
+    /// The position shown, whose scores are shown as well.
+    game: Game,
+    /// Notes on the moves, by the index of the move.
+    comments: BTreeMap<usize, String>,
+    /// Time between two moves, when they are played by themselves.
+    interval: Duration,
+    /// When the last move was played by itself, or `None` while paused.
+    playing: Option<Instant>,
+}
+
+const MIN_REPLAY_INTERVAL: Duration = Duration::from_millis(125);
+const MAX_REPLAY_INTERVAL: Duration = Duration::from_secs(8);
+// Captures beyond this are not listed, as digits choose them.
+const MAX_LISTED_CATCHES: usize = 9;
+const CURSOR_HELP: &str =
+    "Move with the arrows or hjkl, Enter to select, Esc to go back, or type a ring like D4.";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlayHandlerState {
@@ -20,6 +70,20 @@ pub enum PlayHandlerState {
     GetMarble,
     GetCatchData,
     RunGame,
+    /// Only the quit keys work, while waiting for the server.
+    Waiting,
+    /// The summary of the finished game is shown.
+    GameOver,
+    /// The name of a save is typed.
+    SaveGame,
+    /// A saved game is chosen to be loaded.
+    LoadGame,
+    /// A past position is shown, and nothing can be played.
+    Preview,
+    /// A saved game is stepped through, and nothing can be played.
+    Replay,
+    /// The menu is shown before a local game.
+    Menu,
     QuitGame,
 }
 
@@ -31,45 +95,211 @@ pub struct PlayHandler {
     renderer_state: RendererState,
     input_data: Option<GameInputData>,
     output_data: Option<GameOutputData>,
-    game_origin: Coordinate,
+    /// `None` while the terminal is too small to show the board.
+    layout: Option<Layout>,
     explain_primary_text: String,
     explain_supplimentary_text: String,
+    /// Marble chosen to jump, before its landing ring is chosen.
+    catch_start: Option<CoreCoordinate>,
+    /// Column letter typed to jump the cursor, waiting for the row number.
+    pending_column: Option<char>,
+    remote: Option<Remote>,
+    /// Why the last move was refused, until a key is pressed.
+    warning: Option<String>,
+    /// The sides played by the computer.
+    bots: Vec<Bot>,
+    search_limits: SearchLimits,
+    /// Where the game is written after every move.
+    autosave: Option<PathBuf>,
+    // The computer does not play before the menu is left.
+    started: bool,
+    menu: Menu,
+    saves_dir: PathBuf,
+    save_name: String,
+    saves: Vec<SaveEntry>,
+    selected_save: usize,
+    // Where the game goes on after a dialog or a preview is closed.
+    dialog_return: PlayHandlerState,
+    // The game chosen in the load dialog is replayed instead of played.
+    load_for_replay: bool,
+    preview: Option<Preview>,
+    /// Whether moves can be taken back, which never happens over a network.
+    takebacks: bool,
+    /// Whether moves are shown step by step.
+    animations: bool,
+    // The last move, while it is shown.
+    animation: Option<Animation>,
+    replay: Option<Replay>,
+    replay_interval: Duration,
+    theme: Theme,
+    // A networked game cannot be played again from here.
+    networked: bool,
 }
 
 impl PlayHandler {
-    pub fn new(app: App, center: Coordinate, origin: Coordinate) -> (Self, RenderData) {
+    pub fn new(app: App, remote: Option<Remote>, layout: Option<Layout>) -> (Self, RenderData) {
+        let origin = layout.map_or_else(Coordinate::default, |layout| layout.origin);
         let game_board = GameBoard::new(&app.get_current_board(), origin.x, origin.y);
+        let menu = Menu::new(app.get_current_board().kind, app.game().rules(), [false; 2]);
 
-        let play_handler = Self {
+        let mut play_handler = Self {
             app,
             game_board,
-            state: PlayHandlerState::GetPutCoord,
+            state: PlayHandlerState::Menu,
             renderer_state: RendererState::default(),
             input_data: None,
             output_data: None,
-            game_origin: origin,
-            explain_primary_text: String::with_capacity(center.x as usize * 2),
-            explain_supplimentary_text: String::with_capacity(center.x as usize * 2),
-        };
-        let init_render_data = RenderData {
-            state: RendererState::DrawIntro,
-            game_board: play_handler.game_board,
-            players_score: play_handler.app.players_score,
-            total_marble: play_handler.app.total_marble,
-            explain_primary_text: play_handler.explain_primary_text.clone(),
-            explain_supplimentary_text: play_handler.explain_supplimentary_text.clone(),
+            layout,
+            explain_primary_text: String::new(),
+            explain_supplimentary_text: String::new(),
+            catch_start: None,
+            pending_column: None,
+            warning: None,
+            bots: Vec::new(),
+            search_limits: SearchLimits::default(),
+            autosave: None,
+            // A game on a server is set up by the server.
+            started: remote.is_some(),
+            menu,
+            saves_dir: PathBuf::new(),
+            save_name: String::new(),
+            saves: Vec::new(),
+            selected_save: 0,
+            dialog_return: PlayHandlerState::GetPutCoord,
+            load_for_replay: false,
+            preview: None,
+            takebacks: true,
+            animations: true,
+            animation: None,
+            replay: None,
+            replay_interval: Duration::ZERO,
+            theme: Theme::default(),
+            networked: remote.is_some(),
+            remote,
         };
+        if play_handler.remote.is_some() {
+            play_handler.sync_with_remote();
+        }
+        let init_render_data = play_handler.render_data(RendererState::RedrawEntire);
 
         (play_handler, init_render_data)
     }
 
+    /// How long the computer thinks on its moves.
+    pub fn set_search_limits(&mut self, limits: SearchLimits) {
+        self.search_limits = limits;
+    }
+
+    /// Let the computer play `players` in a local game.
+    pub fn set_bots(&mut self, players: &[Player]) {
+        self.bots = players
+            .iter()
+            .map(|&player| Bot::new(player, self.search_limits))
+            .collect();
+        let computers = [Player::Alice, Player::Bob].map(|player| players.contains(&player));
+        self.menu = Menu::new(
+            self.app.get_current_board().kind,
+            self.app.game().rules(),
+            computers,
+        );
+    }
+
+    pub fn set_autosave(&mut self, path: Option<PathBuf>) {
+        self.autosave = path;
+    }
+
+    pub fn set_takebacks(&mut self, takebacks: bool) {
+        self.takebacks = takebacks;
+    }
+
+    pub fn set_animations(&mut self, animations: bool) {
+        self.animations = animations;
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// Time between two moves of a replay which plays by itself.
+    pub fn set_replay_interval(&mut self, interval: Duration) {
+        self.replay_interval = interval;
+    }
+
+    /// Directory of the games saved and loaded from the dialogs.
+    pub fn set_saves_dir(&mut self, dir: PathBuf) {
+        self.saves_dir = dir;
+    }
+
+    /// Keep an unfinished local game, so it can be loaded next time.
+    pub fn save_on_quit(&mut self) -> error::Result<()> {
+        if self.networked
+            || !self.started
+            || self.replay.is_some()
+            || self.app.moves().is_empty()
+            || self.app.result().is_some()
+        {
+            return Ok(());
+        }
+
+        self.app.pause_clock();
+        saves::save(&self.saves_dir, saves::AUTOSAVE_NAME, &self.app)?;
+        Ok(())
+    }
+
     pub fn is_game_end(&self) -> bool {
         self.state == PlayHandlerState::QuitGame
     }
 
     pub fn run_game(&mut self, event: Event) -> error::Result<Option<RenderData>> {
+        if let Event::Resize(columns, rows) = event {
+            self.layout = Layout::new(columns, rows);
+            return Ok(Some(self.render_data(RendererState::RedrawEntire)));
+        }
+
+        // A key or a click skips the animation, and does nothing else.
+        if self.animation.is_some()
+            && matches!(
+                event,
+                Event::Key(_)
+                    | Event::Mouse(MouseEvent {
+                        kind: MouseEventKind::Down(_),
+                        ..
+                    })
+            )
+        {
+            self.animation = None;
+            return Ok(Some(self.render_data(RendererState::RedrawEntire)));
+        }
+
+        // Nothing else is done until the message box is dismissed.
+        if self.warning.is_some() {
+            if let Event::Key(_) = event {
+                self.warning = None;
+                return Ok(Some(self.render_data(RendererState::RedrawEntire)));
+            }
+            return Ok(Some(self.render_data(RendererState::DefaultState)));
+        }
+
         match self.state {
             PlayHandlerState::QuitGame => return Ok(None),
+            PlayHandlerState::Waiting => {
+                if let Event::Key(KeyEvent {
+                    code, modifiers, ..
+                }) = event
+                {
+                    if quit_game(code, modifiers) {
+                        self.state = PlayHandlerState::QuitGame;
+                    }
+                }
+            }
+            PlayHandlerState::GameOver => self.handle_game_over_event(event)?,
+            PlayHandlerState::SaveGame | PlayHandlerState::LoadGame => {
+                self.handle_dialog_event(event)
+            }
+            PlayHandlerState::Preview => self.handle_preview_event(event)?,
+            PlayHandlerState::Replay => self.handle_replay_event(event)?,
+            PlayHandlerState::Menu => self.handle_menu_event(event)?,
+            PlayHandlerState::RunGame if self.remote.is_some() => self.send_remote_move()?,
             PlayHandlerState::GetPutCoord
             | PlayHandlerState::GetRemoveCoord
             | PlayHandlerState::GetMarble
@@ -81,79 +311,1051 @@ impl PlayHandler {
                 }
 
                 match self.app.get_game_state() {
-                    GameState::PutMarble => {
-                        match self.app.play(&self.input_data) {
+                    GameState::PutMarble | GameState::CatchMarble => {
+                        // The game checks the whole move before touching the
+                        // board, so a refused move leaves nothing to undo.
+                        let played = match self.input_move() {
+                            Some(mv) => self.app.play_move(mv),
+                            None => Err(ZertzCoreError::IllegalMove),
+                        };
+                        self.reset_selection();
+                        self.input_data = None;
+                        match played {
                             Ok(()) => {}
-                            // TODO: display errors
+                            // The flag fell before the move was played.
+                            Err(_) if self.app.result().is_some() => {}
                             Err(
-                                ZertzCoreError::InvalidInputData
+                                err @ (ZertzCoreError::IllegalMove
                                 | ZertzCoreError::InvalidPuttingMarble
-                                | ZertzCoreError::InvalidRingToRemove,
+                                | ZertzCoreError::InvalidRingToRemove),
                             ) => {
-                                self.app.rewind();
-                                self.state = PlayHandlerState::GetPutCoord;
-                                return Ok(Some(RenderData {
-                                    state: RendererState::RedrawEntire,
-                                    game_board: self.game_board,
-                                    players_score: self.app.players_score,
-                                    total_marble: self.app.total_marble,
-                                    explain_primary_text: self.explain_primary_text.clone(),
-                                    explain_supplimentary_text: self
-                                        .explain_supplimentary_text
-                                        .clone(),
-                                }));
+                                self.warning = Some(err.to_string());
+                                self.next_turn()?;
+                                return Ok(Some(self.render_data(RendererState::RedrawEntire)));
                             }
                             Err(err) => return Err(err.into()),
                         }
                         self.game_board.update(&self.app.get_current_board());
-                        self.output_data = self.app.get_output();
-                    }
-                    GameState::CatchMarble => {
-                        match self.app.play(&self.input_data) {
-                            Ok(()) => {}
-                            Err(ZertzCoreError::InvalidInputData) => {
-                                self.app.rewind();
-                                self.state = PlayHandlerState::GetCatchData;
-                                return Ok(Some(RenderData {
-                                    state: RendererState::RedrawEntire,
-                                    game_board: self.game_board,
-                                    players_score: self.app.players_score,
-                                    total_marble: self.app.total_marble,
-                                    explain_primary_text: self.explain_primary_text.clone(),
-                                    explain_supplimentary_text: self
-                                        .explain_supplimentary_text
-                                        .clone(),
-                                }));
-                            }
-                            Err(err) => return Err(err.into()),
-                        }
-                        self.game_board.update(&self.app.get_current_board());
-                        self.output_data = self.app.get_output();
+                        self.animate_last_move();
+                        self.autosave();
+                        self.next_turn()?;
                     }
                     GameState::CheckIsCatchable | GameState::FoundSequentialMove => {
                         self.app.play(&None)?;
                         self.output_data = self.app.get_output();
                         match self.app.get_game_state() {
                             GameState::PutMarble => self.state = PlayHandlerState::GetPutCoord,
-                            GameState::CatchMarble => self.state = PlayHandlerState::GetCatchData,
+                            GameState::CatchMarble => {
+                                self.state = PlayHandlerState::GetCatchData;
+                                self.show_catch_options();
+                            }
                             _ => unreachable!(),
                         }
                     }
-                    GameState::GameEnd(_winner) => {
-                        zertz_todo!("run_game cannot handle the `win and end` state");
+                    GameState::GameEnd(_) => self.show_game_over(),
+                }
+            }
+        }
+
+        Ok(Some(self.render_data(self.renderer_state)))
+    }
+
+    /// Handle what the server has sent since the last call. This returns
+    /// `None` when nothing has to be redrawn.
+    pub fn tick(&mut self) -> error::Result<Option<RenderData>> {
+        // Nothing goes on before the last move is shown.
+        if let Some(animation) = self.animation.as_ref() {
+            if animation.is_over(Instant::now()) {
+                self.animation = None;
+            }
+            return Ok(Some(self.render_data(RendererState::DefaultState)));
+        }
+        if self.replay.is_some() {
+            return self.tick_replay();
+        }
+
+        let Some(remote) = self.remote.as_mut() else {
+            if self.state == PlayHandlerState::GameOver || !self.app.check_flag() {
+                return self.tick_bot();
+            }
+            self.show_game_over();
+            return Ok(Some(self.render_data(self.renderer_state)));
+        };
+
+        if self.state == PlayHandlerState::RunGame {
+            self.send_remote_move()?;
+            return Ok(Some(self.render_data(RendererState::UpdateExplanation)));
+        }
+
+        let message = match remote.try_receive() {
+            Ok(Some(message)) => message,
+            Ok(None) => return Ok(None),
+            Err(ZertzTerminalError::ConnectionClosed) => {
+                // The server closes every connection once the game is over.
+                self.remote = None;
+                if !matches!(self.app.get_game_state(), GameState::GameEnd(_)) {
+                    self.state = PlayHandlerState::QuitGame;
+                }
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
+        };
+
+        match message {
+            ServerMessage::State { snapshot } => {
+                remote.players = snapshot.players.clone();
+                let is_full = remote.players.iter().all(|name| !name.is_empty());
+                self.app = snapshot.into_app();
+                // The clock of the server is running once both players are in.
+                if is_full {
+                    self.app.start_clock();
+                }
+                self.game_board.update(&self.app.get_current_board());
+                self.input_data = None;
+                self.catch_start = None;
+                self.sync_with_remote();
+            }
+            ServerMessage::Rejected { reason } => {
+                // The move may have been a capture, so the prompt follows the
+                // game instead of going back to putting a marble.
+                self.reset_selection();
+                self.input_data = None;
+                self.catch_start = None;
+                self.sync_with_remote();
+                self.warning = Some(format!("The server rejected the move: {reason}"));
+            }
+            ServerMessage::Moved { mv } => {
+                // Our copy of the clock may run out a little earlier than the
+                // one of the server, whose next state has the final word.
+                let _ = self.app.play_move(mv);
+                self.game_board.update(&self.app.get_current_board());
+                self.animate_last_move();
+                self.sync_with_remote();
+            }
+            ServerMessage::Welcome { .. } => return Ok(None),
+        }
+
+        Ok(Some(self.render_data(RendererState::RedrawEntire)))
+    }
+
+    // Let the computer think on its turn, and play what it found. This
+    // returns `None` when nothing has to be redrawn.
+    fn tick_bot(&mut self) -> error::Result<Option<RenderData>> {
+        let player = self.app.game().current_player();
+        let Some(bot) = self.bots.iter_mut().find(|bot| bot.player() == player) else {
+            return Ok(None);
+        };
+        if !self.started || self.app.result().is_some() {
+            return Ok(None);
+        }
+
+        if !bot.is_thinking() {
+            // The last move of the other player may not be settled yet.
+            while let GameState::CheckIsCatchable | GameState::FoundSequentialMove =
+                self.app.get_game_state()
+            {
+                self.app.play(&None)?;
+            }
+            self.output_data = self.app.get_output();
+            self.app.start_clock();
+            if let Some(bot) = self.bots.iter_mut().find(|bot| bot.player() == player) {
+                bot.think(self.app.game());
+            }
+
+            self.reset_selection();
+            self.input_data = None;
+            self.catch_start = None;
+            self.state = PlayHandlerState::Waiting;
+            self.clear_explain_text();
+            self.explain_primary_text
+                .push_str(&format!("{} is thinking...", self.player_name(player)));
+            return Ok(Some(self.render_data(RendererState::UpdateExplanation)));
+        }
+
+        let Some(mv) = bot.try_move() else {
+            return Ok(None);
+        };
+        match self.app.play_move(mv) {
+            Ok(()) => {}
+            // The flag fell while the computer was thinking.
+            Err(_) if self.app.result().is_some() => {}
+            Err(err) => return Err(err.into()),
+        }
+        self.game_board.update(&self.app.get_current_board());
+        self.output_data = self.app.get_output();
+        self.animate_last_move();
+        self.autosave();
+
+        self.next_turn()?;
+
+        Ok(Some(self.render_data(RendererState::RedrawEntire)))
+    }
+
+    // Wait for the input of the player to move, after a move which was not
+    // played from the board.
+    fn next_turn(&mut self) -> error::Result<()> {
+        while let GameState::CheckIsCatchable | GameState::FoundSequentialMove =
+            self.app.get_game_state()
+        {
+            self.app.play(&None)?;
+        }
+        self.output_data = self.app.get_output();
+
+        self.clear_explain_text();
+        match self.app.get_game_state() {
+            GameState::GameEnd(_) => self.show_game_over(),
+            GameState::CatchMarble => {
+                self.state = PlayHandlerState::GetCatchData;
+                self.show_catch_options();
+            }
+            _ => {
+                self.state = PlayHandlerState::GetPutCoord;
+                self.explain_primary_text
+                    .push_str("Select the ring (the 'O' characters) where the marble is put.");
+            }
+        }
+
+        Ok(())
+    }
+
+    // Show the move just played step by step.
+    fn animate_last_move(&mut self) {
+        if !self.animations {
+            return;
+        }
+        self.animation = self.app.last_change().cloned().map(Animation::new);
+    }
+
+    fn player_name(&self, player: Player) -> String {
+        match self.app.players()[usize::from(player)].as_str() {
+            "" => format!("{player:?}"),
+            name => name.to_string(),
+        }
+    }
+
+    // The move chosen on the board, if it is complete.
+    fn input_move(&self) -> Option<Move> {
+        match self.input_data {
+            Some(GameInputData {
+                catch_data: Some(catch_data),
+                ..
+            }) => Some(Move::Catch(catch_data)),
+            Some(GameInputData {
+                put_coord: Some(put_coord),
+                remove_coord: Some(remove_coord),
+                marble: Some(marble),
+                ..
+            }) => {
+                // No ring is removed when the board has none to spare.
+                let remove_coord = self
+                    .app
+                    .game()
+                    .legal_moves()
+                    .into_iter()
+                    .find_map(|mv| match mv {
+                        Move::Put {
+                            put_coord: legal_put_coord,
+                            remove_coord: None,
+                            marble: legal_marble,
+                        } if (legal_put_coord, legal_marble) == (put_coord, marble) => Some(None),
+                        _ => None,
+                    })
+                    .unwrap_or(Some(remove_coord));
+                Some(Move::Put {
+                    put_coord,
+                    remove_coord,
+                    marble,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn send_remote_move(&mut self) -> error::Result<()> {
+        let mv = self.input_move();
+
+        self.reset_selection();
+        self.input_data = None;
+        self.state = PlayHandlerState::Waiting;
+        self.clear_explain_text();
+        if let (Some(mv), Some(remote)) = (mv, self.remote.as_mut()) {
+            remote.send_move(mv)?;
+            self.explain_primary_text
+                .push_str("Waiting for the server to accept the move.");
+        }
+
+        Ok(())
+    }
+
+    // Pick the state from the game which the server has sent.
+    fn sync_with_remote(&mut self) {
+        let Some(remote) = self.remote.as_ref() else {
+            return;
+        };
+        // A new move ends any preview.
+        self.preview = None;
+        let player = remote.player;
+        let [alice, bob] = remote.players.clone();
+        let opponent = match remote.opponent_name() {
+            "" => "an opponent to join".to_string(),
+            name => name.to_string(),
+        };
+
+        self.clear_explain_text();
+        self.state = match self.app.get_game_state() {
+            GameState::GameEnd(_) => {
+                self.show_game_over();
+                PlayHandlerState::GameOver
+            }
+            _ if player.is_none() => {
+                self.explain_primary_text.push_str(&format!(
+                    "Watching {alice} (Alice) against {bob} (Bob). {:?} is to move.",
+                    self.app.game().current_player()
+                ));
+                self.explain_supplimentary_text.push_str("Press q to quit.");
+                PlayHandlerState::Waiting
+            }
+            _ if Some(self.app.game().current_player()) != player => {
+                self.explain_primary_text
+                    .push_str(&format!("Waiting for {opponent}..."));
+                PlayHandlerState::Waiting
+            }
+            GameState::CatchMarble => PlayHandlerState::GetCatchData,
+            _ => PlayHandlerState::GetPutCoord,
+        };
+        if self.state == PlayHandlerState::GetCatchData {
+            self.show_catch_options();
+        }
+    }
+
+    // Show the summary if the game is over.
+    fn show_game_over(&mut self) {
+        if self.app.result().is_none() {
+            return;
+        }
+
+        self.reset_selection();
+        self.clear_explain_text();
+        self.state = PlayHandlerState::GameOver;
+        self.renderer_state = RendererState::DrawGameOver;
+    }
+
+    fn handle_game_over_event(&mut self, event: Event) -> error::Result<()> {
+        let Event::Key(KeyEvent {
+            code, modifiers, ..
+        }) = event
+        else {
+            return Ok(());
+        };
+
+        if quit_game(code, modifiers) {
+            self.state = PlayHandlerState::QuitGame;
+            return Ok(());
+        }
+        match (code, modifiers) {
+            (KeyCode::Char('r'), KeyModifiers::NONE) if !self.networked => self.rematch(),
+            (KeyCode::Char('s'), KeyModifiers::NONE) => self.save_record(),
+            (KeyCode::Char('u'), KeyModifiers::NONE) => self.take_back(true)?,
+            (KeyCode::PageUp | KeyCode::Char('['), _) => {
+                self.show_preview(self.app.moves().len().saturating_sub(1))?
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn rematch(&mut self) {
+        self.app = self.app.rematch();
+        self.app.start_clock();
+        self.game_board.update(&self.app.get_current_board());
+        self.reset_selection();
+        self.input_data = None;
+        self.output_data = None;
+        self.catch_start = None;
+        self.clear_explain_text();
+        self.explain_primary_text
+            .push_str("Select the ring (the 'O' characters) where the marble is put.");
+        self.state = PlayHandlerState::GetPutCoord;
+        self.renderer_state = RendererState::RedrawEntire;
+    }
+
+    // The record is saved in the current directory, under the time it is saved.
+    fn save_record(&mut self) {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        let path = format!("zertz-record-{secs}.json");
+
+        let saved = self
+            .app
+            .to_record()
+            .save()
+            .map_err(ZertzTerminalError::from)
+            .and_then(|json| Ok(fs::write(&path, json)?));
+
+        self.clear_explain_text();
+        match saved {
+            Ok(()) => self
+                .explain_primary_text
+                .push_str(&format!("Saved the record to {path}.")),
+            Err(err) => self
+                .explain_primary_text
+                .push_str(&format!("Cannot save the record: {err}")),
+        }
+    }
+
+    // Take back the last move, or play it again when `undo` is false. The
+    // moves of the computer are passed over, so that it is the turn of the
+    // person again.
+    fn take_back(&mut self, undo: bool) -> error::Result<()> {
+        if !self.takebacks || self.networked {
+            return Ok(());
+        }
+
+        let step = |app: &mut App| if undo { app.undo() } else { app.redo() };
+        if let Err(err) = step(&mut self.app) {
+            let action = if undo {
+                "take the move back"
+            } else {
+                "play the move again"
+            };
+            self.warning = Some(format!("Cannot {action}: {err}."));
+            return Ok(());
+        }
+        while self.is_bot_to_move() && step(&mut self.app).is_ok() {}
+
+        self.game_board.update(&self.app.get_current_board());
+        self.reset_selection();
+        self.input_data = None;
+        self.catch_start = None;
+        self.autosave();
+        self.next_turn()?;
+        self.renderer_state = RendererState::RedrawEntire;
+
+        Ok(())
+    }
+
+    fn is_bot_to_move(&self) -> bool {
+        let player = self.app.game().current_player();
+        self.bots.iter().any(|bot| bot.player() == player)
+    }
+
+    // Show the position after the first `shown` moves. The game is shown
+    // again after the last move.
+    fn show_preview(&mut self, shown: usize) -> error::Result<()> {
+        let total = self.app.moves().len();
+        if shown >= total {
+            self.close_preview();
+            return Ok(());
+        }
+
+        let record = GameRecord {
+            moves: self.app.moves()[..shown].to_vec(),
+            ..self.app.to_record()
+        };
+        let game = record.replay()?;
+        let origin = self
+            .layout
+            .map_or_else(Coordinate::default, |layout| layout.origin);
+        let mut game_board = GameBoard::new(game.board(), origin.x, origin.y);
+        game_board.set_cursor(self.game_board.cursor());
+
+        let explanation = match self.preview.take() {
+            Some(preview) => preview.explanation,
+            None => {
+                self.dialog_return = self.state;
+                (
+                    self.explain_primary_text.clone(),
+                    self.explain_supplimentary_text.clone(),
+                )
+            }
+        };
+        self.state = PlayHandlerState::Preview;
+        self.clear_explain_text();
+        self.explain_primary_text
+            .push_str(&format!("Position after move {shown} of {total}."));
+        self.explain_supplimentary_text
+            .push_str("Up/Down or a click chooses another move, and Esc goes back to the game.");
+        self.preview = Some(Preview {
+            shown,
+            game_board,
+            explanation,
+        });
+        self.renderer_state = RendererState::UpdateExplanation;
+
+        Ok(())
+    }
+
+    fn close_preview(&mut self) {
+        if let Some(Preview {
+            explanation: (primary, supplimentary),
+            ..
+        }) = self.preview.take()
+        {
+            self.explain_primary_text = primary;
+            self.explain_supplimentary_text = supplimentary;
+            self.state = self.dialog_return;
+        }
+        self.renderer_state = if self.state == PlayHandlerState::GameOver {
+            RendererState::DrawGameOver
+        } else {
+            RendererState::RedrawEntire
+        };
+    }
+
+    fn handle_preview_event(&mut self, event: Event) -> error::Result<()> {
+        let Some(shown) = self.preview.as_ref().map(|preview| preview.shown) else {
+            return Ok(());
+        };
+
+        match event {
+            Event::Key(KeyEvent {
+                code, modifiers, ..
+            }) => match code {
+                _ if quit_game(code, modifiers) => self.state = PlayHandlerState::QuitGame,
+                KeyCode::Up | KeyCode::Char('k') | KeyCode::PageUp | KeyCode::Char('[') => {
+                    self.show_preview(shown.saturating_sub(1))?
+                }
+                KeyCode::Down | KeyCode::Char('j') | KeyCode::PageDown | KeyCode::Char(']') => {
+                    self.show_preview(shown + 1)?
+                }
+                KeyCode::Home => self.show_preview(0)?,
+                KeyCode::End | KeyCode::Enter | KeyCode::Esc => self.close_preview(),
+                _ => {}
+            },
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) => {
+                if let Some(shown) = self.clicked_move(column, row) {
+                    self.show_preview(shown)?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    // Number of moves to the position after the move clicked in the history.
+    fn clicked_move(&self, column: u16, row: u16) -> Option<usize> {
+        let line = renderer::history_line_at(self.layout?, column, row)?;
+        let history = self.move_history();
+        let idx = renderer::history_window(history.moves.len(), history.selected).start + line;
+
+        (idx < history.moves.len()).then_some(idx + 1)
+    }
+
+    /// Step through the game instead of playing it, with `comments` on its
+    /// moves.
+    pub fn start_replay(&mut self, comments: BTreeMap<usize, String>) -> error::Result<RenderData> {
+        self.open_replay(comments)?;
+
+        Ok(self.render_data(RendererState::RedrawEntire))
+    }
+
+    fn open_replay(&mut self, comments: BTreeMap<usize, String>) -> error::Result<()> {
+        // Nobody is on the move, so no time is spent.
+        self.app.set_time_control(None);
+        self.started = true;
+        self.state = PlayHandlerState::Replay;
+        self.replay = Some(Replay {
+            shown: 0,
+            game: Game::with_rules(self.app.get_current_board().kind, self.app.game().rules()),
+            comments,
+            interval: self.replay_interval,
+            playing: None,
+        });
+
+        self.replay_to(0)
+    }
+
+    // Show the position after the first `shown` moves, or after the last.
+    fn replay_to(&mut self, shown: usize) -> error::Result<()> {
+        let total = self.app.moves().len();
+        let shown = shown.min(total);
+        let record = GameRecord {
+            moves: self.app.moves()[..shown].to_vec(),
+            ..self.app.to_record()
+        };
+        let game = record.replay()?;
+        let cursor = self.game_board.cursor();
+        let origin = self
+            .layout
+            .map_or_else(Coordinate::default, |layout| layout.origin);
+        self.game_board = GameBoard::new(game.board(), origin.x, origin.y);
+        self.game_board.set_cursor(cursor);
+
+        let players = [Player::Alice, Player::Bob].map(|player| self.player_name(player));
+        let result = self.app.result().filter(|_| shown == total).map(|result| {
+            renderer::describe_result(
+                result,
+                &players,
+                self.app.game().players_score(),
+                self.app.game().rules().win_condition(),
+            )
+        });
+        let Some(replay) = self.replay.as_mut() else {
+            return Ok(());
+        };
+        replay.shown = shown;
+        replay.game = game;
+        if shown == total {
+            replay.playing = None;
+        }
+
+        let mut primary = format!("Move {shown} of {total}.");
+        if let Some(comment) = shown
+            .checked_sub(1)
+            .and_then(|idx| replay.comments.get(&idx))
+        {
+            primary.push_str(&format!(" {comment}"));
+        }
+        if let Some(result) = result {
+            primary.push_str(&format!(" {result}"));
+        }
+        let playing = match replay.playing {
+            Some(_) => "Space pauses",
+            None => "Space plays",
+        };
+        let supplimentary = format!(
+            "Left/Right steps, Home/End jumps, {playing} a move every {:.1}s (+/- to change), q quits.",
+            replay.interval.as_secs_f32()
+        );
+        self.explain_primary_text = primary;
+        self.explain_supplimentary_text = supplimentary;
+        self.renderer_state = RendererState::RedrawEntire;
+
+        Ok(())
+    }
+
+    fn handle_replay_event(&mut self, event: Event) -> error::Result<()> {
+        let Some(shown) = self.replay.as_ref().map(|replay| replay.shown) else {
+            return Ok(());
+        };
+
+        match event {
+            Event::Key(KeyEvent {
+                code, modifiers, ..
+            }) => match code {
+                _ if quit_game(code, modifiers) => self.state = PlayHandlerState::QuitGame,
+                KeyCode::Left | KeyCode::Char('h' | 'k') | KeyCode::Up | KeyCode::PageUp => {
+                    self.replay_to(shown.saturating_sub(1))?
+                }
+                KeyCode::Right | KeyCode::Char('l' | 'j') | KeyCode::Down | KeyCode::PageDown => {
+                    self.replay_to(shown + 1)?
+                }
+                KeyCode::Home | KeyCode::Char('g') => self.replay_to(0)?,
+                KeyCode::End | KeyCode::Char('G') => self.replay_to(usize::MAX)?,
+                KeyCode::Char(' ') => self.toggle_replay()?,
+                KeyCode::Char('+' | '=') => self.change_replay_interval(|interval| interval / 2)?,
+                KeyCode::Char('-') => self.change_replay_interval(|interval| interval * 2)?,
+                _ => {}
+            },
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) => {
+                if let Some(shown) = self.clicked_move(column, row) {
+                    self.replay_to(shown)?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    // Play the moves by themselves, or pause. Playing from the end starts
+    // again from the beginning.
+    fn toggle_replay(&mut self) -> error::Result<()> {
+        let total = self.app.moves().len();
+        let Some(replay) = self.replay.as_mut() else {
+            return Ok(());
+        };
+        let shown = match replay.playing {
+            None if replay.shown == total => 0,
+            _ => replay.shown,
+        };
+        replay.playing = match replay.playing {
+            Some(_) => None,
+            None => Some(Instant::now()),
+        };
+
+        self.replay_to(shown)
+    }
+
+    fn change_replay_interval(
+        &mut self,
+        f: impl FnOnce(Duration) -> Duration,
+    ) -> error::Result<()> {
+        let Some(replay) = self.replay.as_mut() else {
+            return Ok(());
+        };
+        replay.interval = f(replay.interval).clamp(MIN_REPLAY_INTERVAL, MAX_REPLAY_INTERVAL);
+        let shown = replay.shown;
+
+        self.replay_to(shown)
+    }
+
+    // Play the next move, once it is time while the replay is playing.
+    fn tick_replay(&mut self) -> error::Result<Option<RenderData>> {
+        let Some(replay) = self.replay.as_mut() else {
+            return Ok(None);
+        };
+        let Some(last_step) = replay.playing else {
+            return Ok(None);
+        };
+        if last_step.elapsed() < replay.interval {
+            return Ok(None);
+        }
+
+        replay.playing = Some(Instant::now());
+        let shown = replay.shown;
+        self.replay_to(shown + 1)?;
+
+        Ok(Some(self.render_data(self.renderer_state)))
+    }
+
+    // Every move with its number and the letter of the player.
+    fn move_history(&self) -> MoveHistory {
+        let mut moves = Vec::with_capacity(self.app.moves().len());
+        let _ = self.app.to_record().for_each_position(|game, mv| {
+            let player = match game.current_player() {
+                Player::Alice => 'A',
+                _ => 'B',
+            };
+            moves.push(format!("{:>3}. {player} {mv}", moves.len() + 1));
+        });
+
+        let shown = match (&self.preview, &self.replay) {
+            (Some(preview), _) => preview.shown,
+            (None, Some(replay)) => replay.shown,
+            (None, None) => moves.len(),
+        };
+        MoveHistory {
+            selected: shown.checked_sub(1),
+            moves,
+        }
+    }
+
+    fn handle_menu_event(&mut self, event: Event) -> error::Result<()> {
+        let action = match event {
+            Event::Key(KeyEvent {
+                code, modifiers, ..
+            }) => match code {
+                _ if quit_game(code, modifiers) => Some(MenuAction::Quit),
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.menu.up();
+                    None
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.menu.down();
+                    None
+                }
+                KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => self.menu.choose(true),
+                KeyCode::Left | KeyCode::Char('h') => self.menu.choose(false),
+                KeyCode::Esc => {
+                    self.menu.back();
+                    None
+                }
+                _ => None,
+            },
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) => {
+                let dialog = self.menu.dialog(&self.settings());
+                self.layout
+                    .and_then(|layout| renderer::dialog_line_at(layout, &dialog, column, row))
+                    .and_then(|line| self.menu.click(line))
+            }
+            _ => None,
+        };
+        self.renderer_state = RendererState::RedrawEntire;
+
+        match action {
+            None => {}
+            Some(MenuAction::Play) => self.start_game()?,
+            Some(MenuAction::NewGame {
+                board_kind,
+                rules,
+                computers,
+            }) => {
+                let mut app = App::with_rules(board_kind, rules);
+                app.set_time_control(self.app.clock().map(Clock::control));
+                let mut players = self.app.players().clone();
+                for (name, computer) in players.iter_mut().zip(computers) {
+                    if computer && name.is_empty() {
+                        *name = "Computer".to_string();
+                    } else if !computer && name == "Computer" {
+                        name.clear();
+                    }
+                }
+                app.set_players(players);
+                let bots = [Player::Alice, Player::Bob]
+                    .into_iter()
+                    .filter(|&player| computers[usize::from(player)])
+                    .collect::<Vec<_>>();
+                self.set_bots(&bots);
+                self.load_game(app)?;
+            }
+            Some(MenuAction::Continue) => {
+                let path = saves::path(&self.saves_dir, saves::AUTOSAVE_NAME);
+                if !path.exists() {
+                    self.warning = Some("There is no unfinished game to continue.".to_string());
+                } else if let Err(err) = saves::load(&path).and_then(|app| self.load_game(app)) {
+                    self.warning = Some(format!("Cannot load the game: {err}"));
+                }
+            }
+            Some(MenuAction::Load) => self.open_load_dialog(),
+            Some(MenuAction::Replay) => {
+                self.open_load_dialog();
+                self.load_for_replay = self.state == PlayHandlerState::LoadGame;
+            }
+            Some(MenuAction::CycleTheme { forward }) => {
+                let presets = ThemePreset::value_variants();
+                let preset = presets.iter().find(|preset| preset.theme() == self.theme);
+                self.theme = match preset {
+                    Some(&preset) => menu::cycle(presets, preset, forward),
+                    // A theme of a file goes to the first preset.
+                    None => presets[0],
+                }
+                .theme();
+            }
+            Some(MenuAction::ToggleAnimations) => self.animations = !self.animations,
+            Some(MenuAction::ToggleTakebacks) => self.takebacks = !self.takebacks,
+            Some(MenuAction::Quit) => self.state = PlayHandlerState::QuitGame,
+        }
+
+        Ok(())
+    }
+
+    // Leave the menu for the game as it is.
+    fn start_game(&mut self) -> error::Result<()> {
+        self.started = true;
+        self.app.start_clock();
+        self.next_turn()?;
+        self.renderer_state = RendererState::RedrawEntire;
+
+        Ok(())
+    }
+
+    // What the settings page of the menu shows.
+    fn settings(&self) -> Settings {
+        let theme = ThemePreset::value_variants()
+            .iter()
+            .find(|preset| preset.theme() == self.theme)
+            .and_then(|preset| preset.to_possible_value())
+            .map_or_else(
+                || "custom".to_string(),
+                |value| value.get_name().to_string(),
+            );
+
+        Settings {
+            theme,
+            animations: self.animations,
+            takebacks: self.takebacks,
+        }
+    }
+
+    fn open_dialog(&mut self, state: PlayHandlerState) {
+        self.app.pause_clock();
+        self.dialog_return = self.state;
+        self.state = state;
+        self.renderer_state = RendererState::UpdateExplanation;
+    }
+
+    fn close_dialog(&mut self) {
+        self.state = self.dialog_return;
+        if self.started {
+            self.app.start_clock();
+        }
+        self.renderer_state = RendererState::RedrawEntire;
+    }
+
+    fn open_load_dialog(&mut self) {
+        match saves::list(&self.saves_dir) {
+            Ok(saves) if saves.is_empty() => {
+                self.warning = Some(format!(
+                    "There is no saved game in {}.",
+                    self.saves_dir.display()
+                ));
+            }
+            Ok(saves) => {
+                self.saves = saves;
+                self.selected_save = 0;
+                self.open_dialog(PlayHandlerState::LoadGame);
+            }
+            Err(err) => self.warning = Some(format!("Cannot list the saved games: {err}")),
+        }
+    }
+
+    fn handle_dialog_event(&mut self, event: Event) {
+        let Event::Key(KeyEvent {
+            code, modifiers, ..
+        }) = event
+        else {
+            return;
+        };
+
+        match (self.state, code) {
+            (_, KeyCode::Esc) => {
+                self.load_for_replay = false;
+                self.close_dialog();
+            }
+            (PlayHandlerState::SaveGame, KeyCode::Enter) if !self.save_name.is_empty() => {
+                let saved = saves::save(&self.saves_dir, &self.save_name, &self.app);
+                self.close_dialog();
+                match saved {
+                    Ok(path) => {
+                        self.clear_explain_text();
+                        self.explain_primary_text
+                            .push_str(&format!("Saved the game to {}.", path.display()));
                     }
+                    Err(err) => self.warning = Some(format!("Cannot save the game: {err}")),
+                }
+            }
+            (PlayHandlerState::SaveGame, KeyCode::Backspace) => {
+                self.save_name.pop();
+            }
+            (PlayHandlerState::SaveGame, KeyCode::Char(c))
+                if saves::is_name_char(c)
+                    && matches!(modifiers, KeyModifiers::NONE | KeyModifiers::SHIFT) =>
+            {
+                self.save_name.push(c);
+            }
+            (PlayHandlerState::LoadGame, KeyCode::Up | KeyCode::Char('k')) => {
+                self.selected_save = self.selected_save.saturating_sub(1);
+            }
+            (PlayHandlerState::LoadGame, KeyCode::Down | KeyCode::Char('j')) => {
+                self.selected_save = (self.selected_save + 1).min(self.saves.len() - 1);
+            }
+            (PlayHandlerState::LoadGame, KeyCode::Enter) => {
+                let loaded = saves::load(&self.saves[self.selected_save].path);
+                self.close_dialog();
+                let loaded = if std::mem::take(&mut self.load_for_replay) {
+                    loaded.and_then(|app| {
+                        self.app = app;
+                        self.open_replay(BTreeMap::new())
+                    })
+                } else {
+                    loaded.and_then(|app| self.load_game(app))
+                };
+                if let Err(err) = loaded {
+                    self.warning = Some(format!("Cannot load the game: {err}"));
                 }
             }
+            _ => {}
+        }
+    }
+
+    // Play `app` from where it was left, which leaves the menu as well.
+    fn load_game(&mut self, app: App) -> error::Result<()> {
+        self.app = app;
+        // The board may be of another kind, where the cursor is off the rings.
+        let origin = self
+            .layout
+            .map_or_else(Coordinate::default, |layout| layout.origin);
+        self.game_board = GameBoard::new(&self.app.get_current_board(), origin.x, origin.y);
+        self.input_data = None;
+        self.output_data = None;
+        self.catch_start = None;
+
+        self.start_game()
+    }
+
+    // Keep the game on the disk after every move, so it can be resumed.
+    fn autosave(&mut self) {
+        let Some(path) = self.autosave.as_ref() else {
+            return;
+        };
+
+        let saved = self
+            .app
+            .save()
+            .map_err(ZertzTerminalError::from)
+            .and_then(|json| Ok(fs::write(path, json)?));
+        if let Err(err) = saved {
+            self.warning = Some(format!("Cannot save the game to {}: {err}", path.display()));
         }
+    }
+
+    fn reset_selection(&mut self) {
+        for coord in CoordinateIter::new() {
+            self.game_board[coord].focused = false;
+            self.game_board[coord].selected = false;
+        }
+    }
+
+    fn render_data(&self, state: RendererState) -> RenderData {
+        let state = match self.warning {
+            Some(_) => RendererState::DrawWarningMsgbox,
+            None => state,
+        };
 
-        return Ok(Some(RenderData {
-            state: self.renderer_state,
-            game_board: self.game_board,
-            players_score: self.app.players_score,
-            total_marble: self.app.total_marble,
+        RenderData {
+            state,
+            game_board: match (&self.preview, &self.animation) {
+                (Some(preview), _) => preview.game_board,
+                (None, Some(animation)) => animation
+                    .frame(self.game_board, Instant::now())
+                    .unwrap_or(self.game_board),
+                (None, None) => self.game_board,
+            },
+            players_score: self
+                .replay
+                .as_ref()
+                .map_or(self.app.players_score, |replay| replay.game.players_score()),
+            total_marble: self
+                .replay
+                .as_ref()
+                .map_or(self.app.total_marble, |replay| replay.game.total_marble()),
+            win_condition: self.app.game().rules().win_condition(),
+            players: [Player::Alice, Player::Bob].map(|player| self.player_name(player)),
+            to_move: match &self.replay {
+                Some(replay) => match replay.game.game_state() {
+                    GameState::GameEnd(_) => None,
+                    _ => Some(replay.game.current_player()),
+                },
+                None => match self.app.get_game_state() {
+                    GameState::GameEnd(_) => None,
+                    _ => Some(self.app.game().current_player()),
+                },
+            },
             explain_primary_text: self.explain_primary_text.clone(),
             explain_supplimentary_text: self.explain_supplimentary_text.clone(),
-        }));
+            clock: self.app.clock().cloned(),
+            summary: self
+                .app
+                .result()
+                .filter(|_| self.state == PlayHandlerState::GameOver && self.animation.is_none())
+                .map(|result| GameSummary {
+                    result,
+                    moves: self.app.moves().len(),
+                    can_rematch: !self.networked,
+                    can_take_back: self.takebacks && !self.networked,
+                }),
+            message: self.warning.clone(),
+            history: self.move_history(),
+            dialog: match self.state {
+                PlayHandlerState::SaveGame => Some(Dialog::SaveName(self.save_name.clone())),
+                PlayHandlerState::LoadGame => Some(Dialog::LoadList {
+                    saves: self.saves.iter().map(SaveEntry::describe).collect(),
+                    selected: self.selected_save,
+                }),
+                PlayHandlerState::Menu => Some(self.menu.dialog(&self.settings())),
+                _ => None,
+            },
+            theme: self.theme,
+        }
     }
 
     fn main_game_event_handle(&mut self, event: Event) -> error::Result<()> {
@@ -162,12 +1364,14 @@ impl PlayHandler {
                 self.clear_explain_text();
                 self.explain_primary_text
                     .push_str("Select the ring (the 'O' characters) where the marble is put.");
+                self.explain_supplimentary_text.push_str(CURSOR_HELP);
                 self.renderer_state = RendererState::UpdateExplanation;
             }
             PlayHandlerState::GetRemoveCoord => {
                 self.clear_explain_text();
                 self.explain_primary_text
                     .push_str("Select the ring (the 'X' characters) to remove.");
+                self.explain_supplimentary_text.push_str(CURSOR_HELP);
                 for coord in self.app.get_removable_rings().into_iter() {
                     self.game_board[coord].focused = true;
                 }
@@ -181,9 +1385,7 @@ impl PlayHandler {
                     .push_str("Input w/W for white, g/G for gray, and b/B for black.");
                 self.renderer_state = RendererState::UpdateExplanation;
             }
-            PlayHandlerState::GetCatchData => {
-                zertz_todo!("main_game_event_handle GetCatchData should be implemented");
-            }
+            PlayHandlerState::GetCatchData => self.show_catch_options(),
             _ => unreachable!(),
         }
 
@@ -193,9 +1395,74 @@ impl PlayHandler {
             _ => {}
         }
 
+        // The highlight follows the chosen marble at once.
+        if self.state == PlayHandlerState::GetCatchData {
+            self.show_catch_options();
+        }
+
         Ok(())
     }
 
+    fn catchable_moves(&self) -> Vec<CatchableMove> {
+        self.app
+            .game()
+            .legal_moves()
+            .into_iter()
+            .filter_map(|mv| match mv {
+                Move::Catch(catch_data) => Some(catch_data),
+                Move::Put { .. } => None,
+            })
+            .collect()
+    }
+
+    // Highlight the marbles which can jump, or the rings where the chosen
+    // marble can land, and list every capture with a number.
+    fn show_catch_options(&mut self) {
+        let catchable_moves = self.catchable_moves();
+
+        self.reset_selection();
+        for catch_data in catchable_moves.iter() {
+            match self.catch_start {
+                None => self.game_board[catch_data.start_coord()].focused = true,
+                Some(start_coord) if start_coord == catch_data.start_coord() => {
+                    self.game_board[start_coord].selected = true;
+                    self.game_board[catch_data.marble_land_coord()].focused = true;
+                }
+                Some(_) => {}
+            }
+        }
+
+        self.clear_explain_text();
+        if self.catch_start.is_none() {
+            self.explain_primary_text
+                .push_str("Select the marble (the green '@' characters) which jumps, or a number:");
+        } else {
+            self.explain_primary_text.push_str(
+                "Select the ring (the 'X' characters) where it lands, or Esc to choose again:",
+            );
+        }
+        let list = catchable_moves
+            .iter()
+            .take(MAX_LISTED_CATCHES)
+            .enumerate()
+            .map(|(idx, catch_data)| format!("{}: {}", idx + 1, Move::Catch(*catch_data)))
+            .collect::<Vec<_>>();
+        self.explain_supplimentary_text.push_str(&list.join("  "));
+        if catchable_moves.len() > MAX_LISTED_CATCHES {
+            self.explain_supplimentary_text.push_str("  ...");
+        }
+        self.renderer_state = RendererState::UpdateExplanation;
+    }
+
+    fn choose_catch(&mut self, catch_data: CatchableMove) {
+        self.input_data = Some(GameInputData {
+            catch_data: Some(catch_data),
+            ..GameInputData::default()
+        });
+        self.catch_start = None;
+        self.state = PlayHandlerState::RunGame;
+    }
+
     fn handle_key_event(&mut self, key_event: KeyEvent) -> error::Result<()> {
         let KeyEvent {
             code,
@@ -209,41 +1476,153 @@ impl PlayHandler {
             return Ok(());
         }
 
+        if !self.networked && modifiers == KeyModifiers::CONTROL {
+            match code {
+                KeyCode::Char('s') => {
+                    self.open_dialog(PlayHandlerState::SaveGame);
+                    return Ok(());
+                }
+                KeyCode::Char('o') => {
+                    self.open_load_dialog();
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+
+        match (code, modifiers) {
+            (KeyCode::PageUp | KeyCode::Char('['), _) => {
+                return self.show_preview(self.app.moves().len().saturating_sub(1));
+            }
+            (KeyCode::Char('u'), KeyModifiers::NONE) => return self.take_back(true),
+            (KeyCode::Char('r'), KeyModifiers::CONTROL) => return self.take_back(false),
+            _ => {}
+        }
+
+        if self.state != PlayHandlerState::GetMarble && self.handle_cursor_key(code, modifiers) {
+            return Ok(());
+        }
+
         match self.state {
             PlayHandlerState::GetPutCoord => {}
-            PlayHandlerState::GetRemoveCoord => {}
+            PlayHandlerState::GetRemoveCoord => {
+                if code == KeyCode::Esc {
+                    self.reset_selection();
+                    self.input_data = None;
+                    self.state = PlayHandlerState::GetPutCoord;
+                }
+            }
             PlayHandlerState::GetMarble => {
-                if modifiers == KeyModifiers::NONE {
+                if code == KeyCode::Esc {
+                    if let Some(input_data) = self.input_data.as_mut() {
+                        input_data.remove_coord = None;
+                    }
+                    self.reset_selection();
+                    if let Some(put_coord) =
+                        self.input_data.as_ref().and_then(|data| data.put_coord)
+                    {
+                        self.game_board[put_coord].selected = true;
+                    }
+                    for coord in self.app.get_removable_rings().into_iter() {
+                        self.game_board[coord].focused = true;
+                    }
+                    self.state = PlayHandlerState::GetRemoveCoord;
+                } else if modifiers == KeyModifiers::NONE {
                     match code {
                         KeyCode::Char('w') => {
-                            self.input_data
-                                .as_mut()
-                                .map(|input_data| input_data.marble = Some(Marble::White));
+                            if let Some(input_data) = self.input_data.as_mut() {
+                                input_data.marble = Some(Marble::White);
+                            }
                             self.state = PlayHandlerState::RunGame;
                         }
                         KeyCode::Char('g') => {
-                            self.input_data
-                                .as_mut()
-                                .map(|input_data| input_data.marble = Some(Marble::Gray));
+                            if let Some(input_data) = self.input_data.as_mut() {
+                                input_data.marble = Some(Marble::Gray);
+                            }
                             self.state = PlayHandlerState::RunGame;
                         }
                         KeyCode::Char('b') => {
-                            self.input_data
-                                .as_mut()
-                                .map(|input_data| input_data.marble = Some(Marble::Black));
+                            if let Some(input_data) = self.input_data.as_mut() {
+                                input_data.marble = Some(Marble::Black);
+                            }
                             self.state = PlayHandlerState::RunGame;
                         }
                         _ => {}
                     }
                 }
             }
-            PlayHandlerState::GetCatchData => {}
+            PlayHandlerState::GetCatchData => match (code, modifiers) {
+                (KeyCode::Char(digit @ '1'..='9'), KeyModifiers::NONE) => {
+                    let idx = digit as usize - '1' as usize;
+                    if let Some(&catch_data) = self.catchable_moves().get(idx) {
+                        self.choose_catch(catch_data);
+                    }
+                }
+                (KeyCode::Esc, _) => self.catch_start = None,
+                _ => {}
+            },
             _ => unreachable!(),
         }
 
         Ok(())
     }
 
+    // Move the cursor, or select the ring under it. This returns whether the
+    // key was used.
+    fn handle_cursor_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        if !matches!(modifiers, KeyModifiers::NONE | KeyModifiers::SHIFT) {
+            return false;
+        }
+
+        let pending_column = self.pending_column.take();
+        let row = self.game_board.cursor().y;
+        let directions: &[Direction] = match code {
+            KeyCode::Left | KeyCode::Char('h') => &[Direction::LEFT],
+            KeyCode::Right | KeyCode::Char('l') => &[Direction::RIGHT],
+            // Going up and down zigzags, so the cursor stays in its column.
+            KeyCode::Up | KeyCode::Char('k') if row.is_multiple_of(2) => {
+                &[Direction::UP.union(Direction::RIGHT), Direction::UP]
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                &[Direction::UP, Direction::UP.union(Direction::RIGHT)]
+            }
+            KeyCode::Down | KeyCode::Char('j') if row.is_multiple_of(2) => {
+                &[Direction::DOWN, Direction::DOWN.union(Direction::LEFT)]
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                &[Direction::DOWN.union(Direction::LEFT), Direction::DOWN]
+            }
+            KeyCode::Enter => {
+                self.select_ring(self.game_board.cursor());
+                return true;
+            }
+            KeyCode::Char(column @ 'A'..='I') => {
+                self.pending_column = Some(column.to_ascii_lowercase());
+                return true;
+            }
+            KeyCode::Char(row @ '1'..='9') => match pending_column {
+                Some(column) => {
+                    if let Ok(coord) = notation::parse_coordinate(&format!("{column}{row}")) {
+                        if self.game_board[coord].kind != Ring::Empty {
+                            self.game_board.set_cursor(coord);
+                        }
+                    }
+                    return true;
+                }
+                None => return false,
+            },
+            _ => return false,
+        };
+
+        for &direction in directions {
+            if self.game_board.move_cursor(direction) {
+                break;
+            }
+        }
+
+        true
+    }
+
     fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> error::Result<()> {
         let MouseEvent {
             kind,
@@ -252,61 +1631,69 @@ impl PlayHandler {
             modifiers,
         } = mouse_event;
 
+        if (kind, modifiers) != (MouseEventKind::Down(MouseButton::Left), KeyModifiers::NONE) {
+            return Ok(());
+        }
+        if let Some(shown) = self.clicked_move(column, row) {
+            return self.show_preview(shown);
+        }
+        let Some(layout) = self.layout else {
+            return Ok(());
+        };
+        if let Some(coord) = Coordinate::new(column, row).into_core_coord(layout.origin) {
+            self.game_board.set_cursor(coord);
+            self.select_ring(coord);
+        }
+
+        Ok(())
+    }
+
+    // Choose `valid_coord` for the current phase, with the mouse or the
+    // keyboard.
+    fn select_ring(&mut self, valid_coord: CoreCoordinate) {
         match self.state {
             PlayHandlerState::GetPutCoord => {
-                if (kind, modifiers)
-                    == (MouseEventKind::Down(MouseButton::Left), KeyModifiers::NONE)
-                {
-                    let valid_coord = if let Some(coord) =
-                        Coordinate::new(column, row).into_core_coord(self.game_origin)
-                    {
-                        coord
-                    } else {
-                        return Ok(());
-                    };
-
-                    self.game_board[valid_coord].selected = true;
-
-                    self.input_data = Some(GameInputData {
-                        put_coord: Some(valid_coord),
-                        remove_coord: None,
-                        marble: None,
-                        catch_data: None,
-                    });
-                    self.state = PlayHandlerState::GetRemoveCoord;
-                }
+                self.game_board[valid_coord].selected = true;
+
+                self.input_data = Some(GameInputData {
+                    put_coord: Some(valid_coord),
+                    remove_coord: None,
+                    marble: None,
+                    catch_data: None,
+                });
+                self.state = PlayHandlerState::GetRemoveCoord;
             }
             PlayHandlerState::GetRemoveCoord => {
-                if (kind, modifiers)
-                    == (MouseEventKind::Down(MouseButton::Left), KeyModifiers::NONE)
-                {
-                    let valid_coord = if let Some(coord) =
-                        Coordinate::new(column, row).into_core_coord(self.game_origin)
-                    {
-                        coord
-                    } else {
-                        return Ok(());
-                    };
-
-                    self.game_board[valid_coord].selected = true;
-                    for coord in self.app.get_removable_rings().into_iter() {
-                        if coord != valid_coord {
-                            self.game_board[coord].focused = false;
-                        }
+                self.game_board[valid_coord].selected = true;
+                for coord in self.app.get_removable_rings().into_iter() {
+                    if coord != valid_coord {
+                        self.game_board[coord].focused = false;
                     }
+                }
 
-                    self.input_data
-                        .as_mut()
-                        .map(|input_data| input_data.remove_coord = Some(valid_coord));
-                    self.state = PlayHandlerState::GetMarble;
+                if let Some(input_data) = self.input_data.as_mut() {
+                    input_data.remove_coord = Some(valid_coord);
                 }
+                self.state = PlayHandlerState::GetMarble;
             }
             PlayHandlerState::GetMarble => {}
-            PlayHandlerState::GetCatchData => {}
+            PlayHandlerState::GetCatchData => {
+                let catchable_moves = self.catchable_moves();
+                let landing = catchable_moves.iter().find(|catch_data| {
+                    Some(catch_data.start_coord()) == self.catch_start
+                        && catch_data.marble_land_coord() == valid_coord
+                });
+                if let Some(&catch_data) = landing {
+                    self.choose_catch(catch_data);
+                } else if catchable_moves
+                    .iter()
+                    .any(|catch_data| catch_data.start_coord() == valid_coord)
+                {
+                    self.catch_start = Some(valid_coord);
+                }
+            }
             _ => unreachable!(),
         }
-
-        Ok(())
     }
 
     fn clear_explain_text(&mut self) {
@@ -0,0 +1,104 @@
+use std::io::BufReader;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+
+use zertz_core::{
+    app::App,
+    game::{Move, Player},
+    net::{self, ClientMessage, ServerMessage},
+};
+
+use crate::error::{self, ZertzTerminalError};
+
+/// Connection to a `zertz-server`, which owns the game. Messages of the
+/// server are read on a thread of their own, so they never block the UI.
+pub struct Remote {
+    stream: TcpStream,
+    messages: Receiver<ServerMessage>,
+    /// Our seat, which is `None` for an observer.
+    pub player: Option<Player>,
+    pub players: [String; 2],
+}
+
+impl Remote {
+    /// Connect to the server and take a seat, returning the current game.
+    pub fn connect(addr: impl ToSocketAddrs, name: &str) -> error::Result<(Self, App)> {
+        let join = ClientMessage::Join {
+            name: name.to_string(),
+        };
+        Self::open(addr, join)
+    }
+
+    /// Connect to the server as an observer, returning the current game.
+    pub fn watch(addr: impl ToSocketAddrs) -> error::Result<(Self, App)> {
+        Self::open(addr, ClientMessage::Watch)
+    }
+
+    fn open(addr: impl ToSocketAddrs, hello: ClientMessage) -> error::Result<(Self, App)> {
+        let mut stream = TcpStream::connect(addr)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        let is_observer = hello == ClientMessage::Watch;
+        net::send(&mut stream, &hello)?;
+
+        let mut player = None;
+        let snapshot = loop {
+            match net::receive::<ServerMessage>(&mut reader)? {
+                Some(ServerMessage::Welcome { player: seat }) => player = Some(seat),
+                Some(ServerMessage::State { snapshot }) if is_observer || player.is_some() => {
+                    break snapshot
+                }
+                Some(ServerMessage::State { .. } | ServerMessage::Moved { .. }) => {}
+                Some(ServerMessage::Rejected { reason }) => {
+                    return Err(ZertzTerminalError::ConnectionRejected(reason))
+                }
+                None => return Err(ZertzTerminalError::ConnectionClosed),
+            }
+        };
+
+        let (message_sender, messages) = channel();
+        thread::spawn(move || {
+            while let Ok(Some(message)) = net::receive::<ServerMessage>(&mut reader) {
+                if message_sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let remote = Self {
+            stream,
+            messages,
+            player,
+            players: snapshot.players.clone(),
+        };
+
+        Ok((remote, snapshot.into_app()))
+    }
+
+    pub fn send_move(&mut self, mv: Move) -> error::Result<()> {
+        Ok(net::send(&mut self.stream, &ClientMessage::Play { mv })?)
+    }
+
+    /// Next message which has arrived, if any.
+    pub fn try_receive(&self) -> error::Result<Option<ServerMessage>> {
+        match self.messages.try_recv() {
+            Ok(message) => Ok(Some(message)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(ZertzTerminalError::ConnectionClosed),
+        }
+    }
+
+    /// Name of the player who is not us, or of Bob for an observer.
+    #[inline]
+    pub fn opponent_name(&self) -> &str {
+        let opponent = self.player.map_or(Player::Bob, Player::opponent);
+        &self.players[usize::from(opponent)]
+    }
+}
+
+impl Drop for Remote {
+    fn drop(&mut self) {
+        let _ = net::send(&mut self.stream, &ClientMessage::Leave);
+    }
+}
@@ -1,25 +1,41 @@
+#[cfg(test)]
+mod buffer;
+pub mod canvas;
 pub mod game_board;
+pub mod message_box;
 pub mod rect;
 pub mod shape;
 mod terminal;
 pub mod titlebox;
 
+use std::env;
 use std::fmt::Display;
-use std::time;
+use std::ops::Range;
+use std::time::{self, Duration, Instant};
 
+use clap::ValueEnum;
 use crossterm::{
-    cursor::{Hide, MoveTo, Show},
+    cursor::{Hide, Show},
     event::{DisableMouseCapture, EnableMouseCapture, Event},
-    execute, queue,
-    style::{Print, ResetColor, Stylize},
+    execute,
+    style::{StyledContent, Stylize},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use zertz_core::game::MarbleCount;
+use zertz_core::{
+    app::{EndReason, GameResult},
+    board::Marble,
+    clock::{Clock, TimeControl},
+    game::{MarbleCount, Player, WinCondition},
+};
 
 use crate::coordinate::Coordinate;
-use crate::error::{self, ZertzTerminalError};
+use crate::error;
+use crate::theme::Theme;
 
-use self::{game_board::GameBoard, shape::Shape, titlebox::TitleBox};
+use self::{
+    canvas::Canvas, game_board::GameBoard, message_box::MessageBox, shape::Shape,
+    terminal::Terminal, titlebox::TitleBox,
+};
 
 const BOX_WIDTH: u16 = 55;
 const BOX_HEIGHT: u16 = 21;
@@ -27,68 +43,178 @@ pub const X_OFFSET: u16 = 19;
 pub const Y_OFFSET: u16 = 18;
 pub const PRIMARY_TEXT_OFFSET: u16 = 14;
 pub const SUPPLIMENTARY_TEXT_OFFSET: u16 = 15;
+pub const CLOCK_OFFSET: u16 = 22;
+const SUMMARY_WIDTH: u16 = 53;
+const SUMMARY_HEIGHT: u16 = 12;
+const MESSAGE_WIDTH: u16 = 45;
+const DIALOG_LIST_WIDTH: u16 = 65;
+const MENU_WIDTH: u16 = BOX_WIDTH - 2;
+const HISTORY_WIDTH: u16 = 24;
+const HISTORY_GAP: u16 = 2;
+const HISTORY_LINES: usize = BOX_HEIGHT as usize - 2;
+const SCORE_WIDTH: u16 = 26;
+const SCORE_HEIGHT: u16 = 13;
+const TITLE_OFFSET: u16 = 24;
+// Smallest terminal which fits the compact layout.
+const MIN_COLUMNS: u16 = BOX_WIDTH + 2;
+const MIN_ROWS: u16 = 2 * (BOX_HEIGHT - 4) + 1;
 
 #[derive(Clone)]
 pub struct RenderData {
     pub state: RendererState,
     pub game_board: GameBoard,
     pub players_score: [MarbleCount; 2],
+    /// Marbles left in the shared pool.
     pub total_marble: MarbleCount,
+    pub win_condition: WinCondition,
+    /// Names shown for Alice and Bob.
+    pub players: [String; 2],
+    /// `None` if the game is over.
+    pub to_move: Option<Player>,
     pub explain_primary_text: String,
     pub explain_supplimentary_text: String,
+    pub clock: Option<Clock>,
+    pub summary: Option<GameSummary>,
+    /// Shown in a message box over the board until a key is pressed.
+    pub message: Option<String>,
+    pub dialog: Option<Dialog>,
+    pub history: MoveHistory,
+    pub theme: Theme,
+}
+
+/// Every move played, where `selected` is the last move on the board.
+#[derive(Debug, Clone, Default)]
+pub struct MoveHistory {
+    pub moves: Vec<String>,
+    pub selected: Option<usize>,
+}
+
+/// A box over the board, which takes every key until it is closed.
+#[derive(Debug, Clone)]
+pub enum Dialog {
+    /// The name of a save, while it is typed.
+    SaveName(String),
+    /// Saved games to choose from, described in a line each.
+    LoadList { saves: Vec<String>, selected: usize },
+    /// A page of the menu, where the `selected` line is highlighted.
+    Menu {
+        title: String,
+        lines: Vec<String>,
+        selected: usize,
+    },
+}
+
+/// What is shown when the game is over.
+#[derive(Debug, Clone)]
+pub struct GameSummary {
+    pub result: GameResult,
+    pub moves: usize,
+    pub can_rematch: bool,
+    pub can_take_back: bool,
 }
 
 #[derive(Clone, Copy, Default, PartialEq)]
 pub enum RendererState {
     #[default]
     DefaultState,
-    DrawIntro,
     RedrawEntire,
     UpdateExplanation,
     ErasePrevExplanation,
+    DrawGameOver,
     DrawWarningMsgbox,
 }
 
-#[allow(unused)]
-pub struct Renderer {
-    terminal: terminal::Terminal,
+/// Characters the terminal is able to show.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Charset {
+    /// ASCII unless the locale is UTF-8.
+    #[default]
+    Auto,
+    Unicode,
+    Ascii,
+}
+
+impl Charset {
+    /// Whether nothing but 7-bit ASCII should be printed.
+    pub fn is_ascii(self) -> bool {
+        match self {
+            Self::Auto => !locale_is_utf8(),
+            Self::Unicode => false,
+            Self::Ascii => true,
+        }
+    }
+}
+
+// The first of the variables which is set decides, as in POSIX. Windows keeps
+// its code page elsewhere, and its terminals show Unicode.
+fn locale_is_utf8() -> bool {
+    if cfg!(windows) {
+        return true;
+    }
+
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .is_some_and(|locale| {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+}
+
+/// Where the board is drawn, for a size of the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    pub center: Coordinate,
+    /// Where the rings are placed from, as in [`Coordinate::into_core_coord`].
+    pub origin: Coordinate,
+    /// Only the board is shown, without the title and the panels beside it.
+    pub compact: bool,
+}
+
+impl Layout {
+    /// The layout for a terminal of `columns` by `rows`, which is `None`
+    /// when even the compact layout does not fit.
+    pub fn new(columns: u16, rows: u16) -> Option<Self> {
+        let center = Coordinate::new(columns.saturating_sub(1) >> 1, rows.saturating_sub(1) >> 1);
+
+        let (box_x, compact) = if center.x >= BOX_WIDTH + 10 && center.y >= TITLE_OFFSET {
+            (center.x - BOX_WIDTH - 10, false)
+        } else if center.x > BOX_WIDTH / 2 && center.y > BOX_HEIGHT - 5 {
+            (center.x - BOX_WIDTH / 2, true)
+        } else {
+            return None;
+        };
+        let box_y = center.y - (BOX_HEIGHT - 5);
+
+        Some(Self {
+            center,
+            origin: Coordinate::new(box_x + X_OFFSET, box_y + Y_OFFSET),
+            compact,
+        })
+    }
+}
+
+pub struct Renderer<C: Canvas = Terminal> {
+    canvas: C,
     state: RendererState,
     center: Coordinate,
     origin: Coordinate,
+    compact: bool,
+    // Nothing but a request to enlarge the terminal is drawn.
+    too_small: bool,
     prevent_update: bool,
 }
 
 impl Renderer {
-    pub fn new() -> error::Result<(Self, Coordinate, Coordinate)> {
-        let terminal = terminal::Terminal::new()?;
-        let (width, height) = (terminal.width, terminal.height);
-
-        let (origin_x, x_overflow) = (width >> 1).overflowing_sub(BOX_WIDTH + 10);
-        let (origin_y, y_overflow) = (height >> 1).overflowing_sub(BOX_HEIGHT - 5);
-
-        if x_overflow || y_overflow {
-            Err(ZertzTerminalError::InappropriateTerminalSize)
-        } else {
-            let center = Coordinate::new(width >> 1, height >> 1);
-            let origin = Coordinate::new(origin_x + X_OFFSET, origin_y + Y_OFFSET);
-            Ok((
-                Self {
-                    terminal,
-                    state: RendererState::default(),
-                    center,
-                    origin,
-                    prevent_update: false,
-                },
-                center,
-                origin,
-            ))
-        }
+    pub fn new() -> error::Result<(Self, Option<Layout>)> {
+        Ok(Self::with_canvas(Terminal::new()?))
     }
 
     pub fn enable_raw_mode(&mut self) -> error::Result<()> {
         enable_raw_mode()?;
         execute!(
-            self.terminal.stdout,
+            self.canvas.stdout,
             EnterAlternateScreen,
             EnableMouseCapture,
             Hide
@@ -98,7 +224,7 @@ impl Renderer {
 
     pub fn disable_raw_mode(&mut self) -> error::Result<()> {
         execute!(
-            self.terminal.stdout,
+            self.canvas.stdout,
             LeaveAlternateScreen,
             DisableMouseCapture,
             Show
@@ -107,136 +233,190 @@ impl Renderer {
         Ok(())
     }
 
-    pub fn draw_object<D>(&mut self, drawee: &D, row: u16, col: u16) -> error::Result<()>
-    where
-        D: Display + Stylize,
-    {
-        Ok(queue!(
-            self.terminal.stdout,
-            MoveTo(row, col),
-            Print(drawee),
-            ResetColor,
-        )?)
+    /// Draw `render_data` unless the user did something, in which case the
+    /// event is returned without drawing.
+    pub fn render(&mut self, render_data: &RenderData) -> error::Result<Option<Event>> {
+        let delta = time::Duration::from_millis(10);
+
+        if self.canvas.poll(delta)? {
+            let event = self.canvas.read()?;
+            // The play handler moves the board as well, from the same event.
+            if let Event::Resize(columns, rows) = event {
+                self.canvas.resize(columns, rows);
+                self.set_layout(columns, rows);
+                self.canvas.clear_all()?;
+            }
+            return Ok(Some(event));
+        }
+
+        if !self.too_small {
+            self.canvas.clear()?;
+        }
+        self.draw(render_data)?;
+
+        Ok(None)
+    }
+}
+
+impl<C: Canvas> Renderer<C> {
+    /// A renderer which draws on `canvas`, with the layout for its size.
+    pub fn with_canvas(canvas: C) -> (Self, Option<Layout>) {
+        let mut renderer = Self {
+            canvas,
+            state: RendererState::default(),
+            center: Coordinate::default(),
+            origin: Coordinate::default(),
+            compact: false,
+            too_small: false,
+            prevent_update: false,
+        };
+        let (columns, rows) = renderer.canvas.size();
+        let layout = renderer.set_layout(columns, rows);
+
+        (renderer, layout)
+    }
+
+    /// Draw frames, rings and marbles with nothing but 7-bit ASCII.
+    pub fn set_ascii(&mut self, ascii: bool) {
+        self.canvas.set_ascii(ascii);
+    }
+
+    // `theme` as it can be shown by the canvas.
+    fn theme(&self, theme: Theme) -> Theme {
+        if self.canvas.is_ascii() {
+            theme.to_ascii()
+        } else {
+            theme
+        }
+    }
+
+    fn set_layout(&mut self, columns: u16, rows: u16) -> Option<Layout> {
+        let layout = Layout::new(columns, rows);
+        self.center = Coordinate::new(columns.saturating_sub(1) >> 1, rows.saturating_sub(1) >> 1);
+        match layout {
+            Some(layout) => {
+                self.origin = layout.origin;
+                self.compact = layout.compact;
+                self.too_small = false;
+            }
+            None => self.too_small = true,
+        }
+
+        layout
+    }
+
+    pub fn draw_object<D: Display>(
+        &mut self,
+        drawee: impl Stylize<Styled = StyledContent<D>>,
+        x: u16,
+        y: u16,
+    ) -> error::Result<()> {
+        self.canvas.print_styled(x, y, &drawee.stylize())
     }
 
     pub fn draw_shape(&mut self, shape: &impl Shape) -> error::Result<()> {
-        shape.draw(&mut self.terminal)
+        shape.draw(&mut self.canvas)
     }
 
     #[allow(dead_code)]
     pub fn draw_axis(&mut self) -> error::Result<()> {
         let Coordinate { x, y } = self.center;
 
-        self.draw_object(&"0", x, y)?;
+        self.draw_object("0", x, y)?;
         for i in (1..).take_while(|n| n * 5 < x) {
-            self.draw_object(&format!("{}", i * 5), x + i * 5, y)?;
-            self.draw_object(&format!("-{}", i * 5), x - i * 5, y)?;
+            self.draw_object(format!("{}", i * 5), x + i * 5, y)?;
+            self.draw_object(format!("-{}", i * 5), x - i * 5, y)?;
         }
         for i in (1..).take_while(|n| n * 2 < y) {
-            self.draw_object(&format!("{}", i * 2), x, y + i * 2)?;
-            self.draw_object(&format!("-{}", i * 2), x, y - i * 2)?;
+            self.draw_object(format!("{}", i * 2), x, y + i * 2)?;
+            self.draw_object(format!("-{}", i * 2), x, y - i * 2)?;
         }
 
         Ok(())
     }
 
-    pub fn render(&mut self, render_data: &RenderData) -> error::Result<Event> {
-        let delta = time::Duration::from_millis(10);
-
-        let event = loop {
-            if self.terminal.poll(delta)? {
-                break self.terminal.read()?;
-            }
-
-            let RenderData {
-                state,
-                game_board,
-                players_score,
-                total_marble,
-                explain_primary_text,
-                explain_supplimentary_text,
-            } = render_data;
-
-            self.state = *state;
-            self.render_branch(
-                game_board,
-                *players_score,
-                *total_marble,
-                explain_primary_text,
-                explain_supplimentary_text,
-            )?;
-        };
+    /// Draw `render_data` on the canvas.
+    pub fn draw(&mut self, render_data: &RenderData) -> error::Result<()> {
+        if self.too_small {
+            return self.render_too_small();
+        }
+        self.state = render_data.state;
 
-        Ok(event)
+        self.render_branch(render_data)
     }
 
-    fn render_branch(
-        &mut self,
-        game_board: &GameBoard,
-        players_score: [MarbleCount; 2],
-        total_marble: MarbleCount,
-        explain_primary_text: &str,
-        explain_supplimentary_text: &str,
-    ) -> error::Result<()> {
+    fn render_branch(&mut self, render_data: &RenderData) -> error::Result<()> {
         match self.state {
             RendererState::DefaultState => {}
-            RendererState::DrawIntro => return self.render_game_intro(),
-            RendererState::RedrawEntire => {
+            RendererState::RedrawEntire
+            | RendererState::DrawGameOver
+            | RendererState::DrawWarningMsgbox => {
                 self.state = RendererState::ErasePrevExplanation;
                 self.prevent_update = false;
             }
             RendererState::UpdateExplanation => {
                 self.state = RendererState::ErasePrevExplanation;
             }
-            RendererState::DrawWarningMsgbox => todo!(),
             _ => unreachable!(),
         }
 
-        self.render_game(
-            game_board,
-            players_score,
-            total_marble,
-            explain_primary_text,
-            explain_supplimentary_text,
-        )?;
+        self.render_game(render_data)?;
 
         Ok(())
     }
 
-    fn render_game(
-        &mut self,
-        game_board: &GameBoard,
-        players_score: [MarbleCount; 2],
-        total_marble: MarbleCount,
-        explain_primary_text: &str,
-        explain_supplimentary_text: &str,
-    ) -> error::Result<()> {
-        // Begin Drawing
-        self.terminal.clear()?;
+    fn render_game(&mut self, render_data: &RenderData) -> error::Result<()> {
+        let RenderData {
+            game_board,
+            explain_primary_text,
+            explain_supplimentary_text,
+            clock,
+            summary,
+            message,
+            dialog,
+            history,
+            theme,
+            ..
+        } = render_data;
 
-        self.draw_object(
-            &"Zertz Board Game".bold(),
-            self.center.x - 8,
-            self.center.y - 24,
-        )?;
+        // Begin Drawing
+        if !self.compact {
+            self.draw_object(
+                "Zertz Board Game".bold(),
+                self.center.x - 8,
+                self.center.y - TITLE_OFFSET,
+            )?;
+        }
+        if let Some(clock) = clock {
+            self.render_clock(clock)?;
+        }
 
         if self.state == RendererState::ErasePrevExplanation && !self.prevent_update {
-            self.terminal
+            self.canvas
                 .clear_line(self.center.y + PRIMARY_TEXT_OFFSET)?;
-            self.terminal
+            self.canvas
                 .clear_line(self.center.y + SUPPLIMENTARY_TEXT_OFFSET)?;
+            // Whatever was drawn over the board, like a message box, is gone.
+            let blank = " ".repeat(BOX_WIDTH as usize - 2);
+            for row in 1..BOX_HEIGHT - 1 {
+                self.draw_object(
+                    blank.as_str(),
+                    self.origin.x - X_OFFSET + 1,
+                    self.origin.y - Y_OFFSET + row,
+                )?;
+            }
             self.state = RendererState::default();
             self.prevent_update = true;
         }
 
         self.draw_object(
-            &explain_primary_text,
-            self.center.x - explain_primary_text.len() as u16 / 2,
+            explain_primary_text.as_str(),
+            self.centered(explain_primary_text),
             self.center.y + PRIMARY_TEXT_OFFSET,
         )?;
         self.draw_object(
-            &explain_supplimentary_text,
-            self.center.x - explain_supplimentary_text.len() as u16 / 2,
+            explain_supplimentary_text.as_str(),
+            self.centered(explain_supplimentary_text),
             self.center.y + SUPPLIMENTARY_TEXT_OFFSET,
         )?;
         self.draw_shape(&TitleBox::new(
@@ -246,22 +426,619 @@ impl Renderer {
             BOX_HEIGHT,
             "[ Board ]",
         ))?;
-        self.draw_shape(game_board)?;
+        // Placed by the current layout, even before the play handler has
+        // heard of a resize.
+        let mut game_board = *game_board;
+        game_board.set_origin(self.origin);
+        game_board.set_theme(self.theme(*theme));
+        self.draw_shape(&game_board)?;
+        if !self.compact {
+            self.render_history(history)?;
+            self.render_score(render_data)?;
+        }
+        if summary.is_some() {
+            self.render_game_over(render_data)?;
+        }
+        let center = board_center(self.origin);
+        if let Some(dialog) = dialog {
+            self.draw_shape(&dialog_box(dialog, center))?;
+        }
+        if let Some(message) = message {
+            self.draw_shape(
+                &MessageBox::new(center, MESSAGE_WIDTH, "[ Warning ]", message)
+                    .yellow()
+                    .bold(),
+            )?;
+        }
 
         // End Drawing
-        self.terminal.flush()?;
+        self.canvas.flush()?;
 
         Ok(())
     }
 
-    // TODO: fully implement this
-    fn render_game_intro(&mut self) -> error::Result<()> {
-        let msg = "Press any key to start a game";
-        self.draw_object(
-            &msg.bold(),
-            self.center.x - msg.len() as u16 / 2,
-            self.center.y + PRIMARY_TEXT_OFFSET,
-        )?;
+    fn render_clock(&mut self, clock: &Clock) -> error::Result<()> {
+        let now = Instant::now();
+        let [alice, bob] = [Player::Alice, Player::Bob].map(|player| {
+            let mut text = format_duration(clock.time_left(player, now));
+            if let TimeControl::ByoYomi { .. } = clock.control() {
+                text.push_str(&format!(" ({})", clock.periods_left(player, now)));
+            }
+            // The clock which is running is marked.
+            if clock.is_running() && clock.running() == player {
+                format!("> {player:?} {text} <")
+            } else {
+                format!("  {player:?} {text}  ")
+            }
+        });
+
+        let text = format!("{alice}    {bob}");
+        // Without the title, the clocks are right above the board.
+        let row = if self.compact {
+            self.origin.y - Y_OFFSET - 1
+        } else {
+            self.center.y - CLOCK_OFFSET
+        };
+        self.draw_object(text.as_str(), self.centered(&text), row)
+    }
+
+    // Drawn on the right of the board.
+    fn render_history(&mut self, history: &MoveHistory) -> error::Result<()> {
+        let x = self.origin.x - X_OFFSET + BOX_WIDTH + HISTORY_GAP;
+        let y = self.origin.y - Y_OFFSET;
+        let width = HISTORY_WIDTH as usize - 2;
+
+        let window = history_window(history.moves.len(), history.selected);
+        for row in 0..HISTORY_LINES {
+            let idx = window.start + row;
+            let line = history.moves.get(idx).map_or("", String::as_str);
+            let line = format!("{line:<width$.width$}");
+            if history.selected == Some(idx) {
+                self.draw_object(line.reverse(), x + 1, y + 1 + row as u16)?;
+            } else {
+                self.draw_object(line, x + 1, y + 1 + row as u16)?;
+            }
+        }
+        self.draw_shape(&TitleBox::new(x, y, HISTORY_WIDTH, BOX_HEIGHT, "[ Moves ]"))
+    }
+
+    // Drawn on the right of the move history.
+    fn render_score(&mut self, render_data: &RenderData) -> error::Result<()> {
+        let RenderData {
+            players_score,
+            total_marble,
+            win_condition,
+            players,
+            to_move,
+            theme,
+            ..
+        } = render_data;
+        let x = self.origin.x - X_OFFSET + BOX_WIDTH + HISTORY_GAP + HISTORY_WIDTH + HISTORY_GAP;
+        let y = self.origin.y - Y_OFFSET;
+        let width = SCORE_WIDTH as usize - 4;
+        let marbles = [Marble::White, Marble::Gray, Marble::Black];
+        let theme = self.theme(*theme);
+
+        // Counts are padded, so that fewer digits leave nothing behind.
+        self.draw_object("Pool", x + 2, y + 2)?;
+        for (i, marble) in marbles.into_iter().enumerate() {
+            let column = x + 2 + i as u16 * 7;
+            self.draw_object(theme.marble(marble), column, y + 3)?;
+            self.draw_object(
+                format!(" {:<2}", total_marble.get(marble)),
+                column + 1,
+                y + 3,
+            )?;
+        }
+
+        for (player, row) in [(Player::Alice, y + 5), (Player::Bob, y + 9)] {
+            let score = players_score[usize::from(player)];
+            let name = &players[usize::from(player)];
+            // The player to move is marked.
+            let name = if *to_move == Some(player) {
+                format!("> {name}")
+            } else {
+                format!("  {name}")
+            };
+            self.draw_object(format!("{name:<width$.width$}"), x + 2, row)?;
+
+            for (i, marble) in marbles.into_iter().enumerate() {
+                let column = x + 4 + i as u16 * 7;
+                self.draw_object(theme.marble(marble), column, row + 1)?;
+                let progress = format!("{}/{}", score.get(marble), win_condition.get(marble));
+                self.draw_object(format!(" {progress:<5}"), column + 1, row + 1)?;
+            }
+            let each = marbles
+                .into_iter()
+                .map(|marble| score.get(marble))
+                .min()
+                .unwrap_or_default();
+            let each = format!("each {each}/{}", win_condition.each);
+            self.draw_object(format!("{each:<18}"), x + 4, row + 2)?;
+        }
+
+        self.draw_shape(&TitleBox::new(
+            x,
+            y,
+            SCORE_WIDTH,
+            SCORE_HEIGHT,
+            "[ Marbles ]",
+        ))
+    }
+
+    // Drawn over the middle of the board.
+    fn render_game_over(&mut self, render_data: &RenderData) -> error::Result<()> {
+        let RenderData {
+            players_score,
+            win_condition,
+            players,
+            summary: Some(summary),
+            ..
+        } = render_data
+        else {
+            return Ok(());
+        };
+        let x = self.origin.x - X_OFFSET + (BOX_WIDTH - SUMMARY_WIDTH) / 2;
+        let y = self.origin.y - Y_OFFSET + (BOX_HEIGHT - SUMMARY_HEIGHT) / 2;
+
+        let blank = " ".repeat(SUMMARY_WIDTH as usize - 1);
+        for row in 1..SUMMARY_HEIGHT {
+            self.draw_object(blank.as_str(), x + 1, y + row)?;
+        }
+        self.draw_shape(&TitleBox::new(
+            x,
+            y,
+            SUMMARY_WIDTH,
+            SUMMARY_HEIGHT,
+            "[ Game Over ]",
+        ))?;
+
+        let caught = |player: Player| {
+            let score = players_score[usize::from(player)];
+            let name = players[usize::from(player)]
+                .chars()
+                .take(11)
+                .collect::<String>();
+            format!(
+                "{:<12}{:>6}{:>6}{:>6}",
+                name,
+                score.get(Marble::White),
+                score.get(Marble::Gray),
+                score.get(Marble::Black)
+            )
+        };
+        let mut options = Vec::new();
+        if summary.can_rematch {
+            options.push("r: rematch");
+        }
+        if summary.can_take_back {
+            options.push("u: undo");
+        }
+        options.extend(["s: save the record", "q: quit"]);
+        let options = options.join("  ");
+        let lines = [
+            (
+                2,
+                describe_result(summary.result, players, *players_score, *win_condition),
+            ),
+            (
+                4,
+                format!("{:<12}{:>6}{:>6}{:>6}", "Caught", "White", "Gray", "Black"),
+            ),
+            (5, caught(Player::Alice)),
+            (6, caught(Player::Bob)),
+            (8, format!("Moves played: {}", summary.moves)),
+            (10, options),
+        ];
+        for (row, line) in lines {
+            // Long names are cut off at the frame.
+            let line = line
+                .chars()
+                .take(SUMMARY_WIDTH as usize - 2)
+                .collect::<String>();
+            self.draw_object(
+                line.as_str(),
+                x + SUMMARY_WIDTH.saturating_sub(line.chars().count() as u16) / 2,
+                y + row,
+            )?;
+        }
+
         Ok(())
     }
+
+    // Shown instead of the game until the terminal is large enough.
+    fn render_too_small(&mut self) -> error::Result<()> {
+        let lines = [
+            "The terminal is too small to play.".to_string(),
+            format!(
+                "Please enlarge it to at least {MIN_COLUMNS}x{MIN_ROWS}, or press Ctrl+C to quit."
+            ),
+        ];
+        for (row, line) in lines.iter().enumerate() {
+            self.draw_object(
+                line.as_str(),
+                self.centered(line),
+                self.center.y.saturating_sub(1) + row as u16,
+            )?;
+        }
+        self.canvas.flush()
+    }
+
+    // Column from which `text` is centered on the screen.
+    fn centered(&self, text: &str) -> u16 {
+        self.center.x.saturating_sub(text.len() as u16 / 2)
+    }
+}
+
+/// Moves listed in the history panel, which scrolls to keep the selected
+/// move in sight.
+pub fn history_window(len: usize, selected: Option<usize>) -> Range<usize> {
+    let end = selected
+        .map_or(0, |idx| idx + 1)
+        .max(HISTORY_LINES.min(len));
+    end.saturating_sub(HISTORY_LINES)..end
+}
+
+/// Line of the history panel under the screen position `(column, row)`,
+/// counted from the first line shown.
+pub fn history_line_at(layout: Layout, column: u16, row: u16) -> Option<usize> {
+    if layout.compact {
+        return None;
+    }
+    let origin = layout.origin;
+    let x = origin.x - X_OFFSET + BOX_WIDTH + HISTORY_GAP;
+    let y = origin.y - Y_OFFSET;
+
+    let is_inside = (x + 1..x + HISTORY_WIDTH - 1).contains(&column)
+        && (y + 1..=y + HISTORY_LINES as u16).contains(&row);
+    is_inside.then(|| (row - y - 1) as usize)
+}
+
+/// Line of `dialog` under the screen position `(column, row)`, counted as
+/// the saves or the lines of the menu.
+pub fn dialog_line_at(layout: Layout, dialog: &Dialog, column: u16, row: u16) -> Option<usize> {
+    dialog_box(dialog, board_center(layout.origin)).line_at(column, row)
+}
+
+// Middle of the board box, where boxes over the board are centered.
+fn board_center(origin: Coordinate) -> Coordinate {
+    Coordinate::new(
+        origin.x - X_OFFSET + BOX_WIDTH / 2,
+        origin.y - Y_OFFSET + BOX_HEIGHT / 2,
+    )
+}
+
+fn dialog_box(dialog: &Dialog, center: Coordinate) -> MessageBox {
+    match dialog {
+        Dialog::SaveName(name) => MessageBox::new(
+            center,
+            MESSAGE_WIDTH,
+            "[ Save ]",
+            &format!("Name of the save: {name}_"),
+        )
+        .footer("Enter to save, Esc to cancel."),
+        Dialog::LoadList { saves, selected } => {
+            MessageBox::list(center, DIALOG_LIST_WIDTH, "[ Load ]", saves, *selected)
+                .footer("Up/Down to choose, Enter to load, Esc to cancel.")
+        }
+        Dialog::Menu {
+            title,
+            lines,
+            selected,
+        } => MessageBox::list(center, MENU_WIDTH, title, lines, *selected)
+            .footer("Arrows to choose, Enter to select, Esc back."),
+    }
+}
+
+/// A sentence telling who won and how, with the names of `players`.
+pub fn describe_result(
+    GameResult { winner, reason }: GameResult,
+    players: &[String; 2],
+    players_score: [MarbleCount; 2],
+    win_condition: WinCondition,
+) -> String {
+    let reason = match reason {
+        EndReason::WinCondition => {
+            let score = winner.map(|winner| players_score[usize::from(winner)]);
+            // The first condition met, in the order the rules check them.
+            let color = [Marble::White, Marble::Gray, Marble::Black]
+                .into_iter()
+                .find(|&marble| {
+                    score.is_some_and(|score| score.get(marble) >= win_condition.get(marble))
+                });
+            match color {
+                Some(marble) => format!(
+                    "by catching {} {} marbles",
+                    win_condition.get(marble),
+                    format!("{marble:?}").to_lowercase()
+                ),
+                None => format!("by catching {} marbles of each color", win_condition.each),
+            }
+        }
+        EndReason::Repetition => "by a repeated board".to_string(),
+        EndReason::BoardFilled => "as no marble can be put".to_string(),
+        EndReason::Resignation => "by resignation".to_string(),
+        EndReason::Agreement => "by agreement".to_string(),
+        EndReason::Timeout => "on time".to_string(),
+        EndReason::Adjudication => "by adjudication".to_string(),
+    };
+
+    match winner {
+        Some(winner) => format!("{} won {reason}.", players[usize::from(winner)]),
+        None => format!("The game is drawn {reason}."),
+    }
+}
+
+// Rounded up, so that a clock shows 0:00 only when the flag has fallen.
+fn format_duration(duration: Duration) -> String {
+    let secs = (duration.as_millis() as u64).div_ceil(1000);
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::style::Attribute;
+    use zertz_core::{app::App, board::BoardKind, game::Rules};
+
+    use super::buffer::Buffer;
+    use super::*;
+
+    // Large enough for the full layout, where the board box is at (14, 8).
+    const COLUMNS: u16 = 160;
+    const ROWS: u16 = 50;
+
+    fn renderer(columns: u16, rows: u16) -> Renderer<Buffer> {
+        let mut buffer = Buffer::new(columns, rows);
+        buffer.set_ascii(true);
+        Renderer::with_canvas(buffer).0
+    }
+
+    fn render_data(app: &App) -> RenderData {
+        RenderData {
+            state: RendererState::RedrawEntire,
+            game_board: GameBoard::new(&app.get_current_board(), 0, 0),
+            players_score: app.players_score,
+            total_marble: app.total_marble,
+            win_condition: app.game().rules().win_condition(),
+            players: ["Alice".to_string(), "Computer".to_string()],
+            to_move: Some(app.game().current_player()),
+            explain_primary_text: String::new(),
+            explain_supplimentary_text: String::new(),
+            clock: None,
+            summary: None,
+            message: None,
+            dialog: None,
+            history: MoveHistory::default(),
+            theme: Theme::default(),
+        }
+    }
+
+    // The cells from `(x, y)`, `width` by `height`.
+    fn region(
+        renderer: &Renderer<Buffer>,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Vec<String> {
+        renderer.canvas.lines()[y..y + height]
+            .iter()
+            .map(|line| {
+                let line = line.chars().skip(x).take(width).collect::<String>();
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn score_panel() {
+        let mut app = App::new(BoardKind::Rings37);
+        let mv = app.game().legal_moves()[0];
+        app.play_move(mv).unwrap();
+        let mut renderer = renderer(COLUMNS, ROWS);
+        renderer.render_score(&render_data(&app)).unwrap();
+
+        assert_eq!(
+            region(&renderer, 97, 8, 26, 13),
+            [
+                "+-[ Marbles ]------------+",
+                "|                        |",
+                "| Pool                   |",
+                "| W 5    G 8    B 10     |",
+                "|                        |",
+                "|   Alice                |",
+                "|   W 0/4  G 0/5  B 0/6  |",
+                "|   each 0/3             |",
+                "|                        |",
+                "| > Computer             |",
+                "|   W 0/4  G 0/5  B 0/6  |",
+                "|   each 0/3             |",
+                "+------------------------+",
+            ]
+        );
+    }
+
+    #[test]
+    fn history_panel_keeps_the_selected_move_in_sight() {
+        let mut renderer = renderer(COLUMNS, ROWS);
+        let history = MoveHistory {
+            moves: (1..=30).map(|n| format!("{n:>3}. A a{n}")).collect(),
+            selected: Some(24),
+        };
+        renderer.render_history(&history).unwrap();
+
+        assert_eq!(
+            region(&renderer, 71, 8, 24, 21),
+            [
+                "+-[ Moves ]------------+",
+                "|  7. A a7             |",
+                "|  8. A a8             |",
+                "|  9. A a9             |",
+                "| 10. A a10            |",
+                "| 11. A a11            |",
+                "| 12. A a12            |",
+                "| 13. A a13            |",
+                "| 14. A a14            |",
+                "| 15. A a15            |",
+                "| 16. A a16            |",
+                "| 17. A a17            |",
+                "| 18. A a18            |",
+                "| 19. A a19            |",
+                "| 20. A a20            |",
+                "| 21. A a21            |",
+                "| 22. A a22            |",
+                "| 23. A a23            |",
+                "| 24. A a24            |",
+                "| 25. A a25            |",
+                "+----------------------+",
+            ]
+        );
+        // The selected move is shown in reverse.
+        let selected = renderer.canvas.cell(72, 27).style.attributes;
+        assert!(selected.has(Attribute::Reverse));
+        assert!(!renderer
+            .canvas
+            .cell(72, 26)
+            .style
+            .attributes
+            .has(Attribute::Reverse));
+    }
+
+    #[test]
+    fn game_over_summary() {
+        let app = App::new(BoardKind::Rings37);
+        let mut renderer = renderer(COLUMNS, ROWS);
+        let render_data = RenderData {
+            summary: Some(GameSummary {
+                result: GameResult {
+                    winner: Some(Player::Bob),
+                    reason: EndReason::Timeout,
+                },
+                moves: 12,
+                can_rematch: true,
+                can_take_back: false,
+            }),
+            ..render_data(&app)
+        };
+        renderer.render_game_over(&render_data).unwrap();
+
+        assert_eq!(
+            region(&renderer, 15, 12, 53, 12),
+            [
+                "+-[ Game Over ]-------------------------------------+",
+                "|                                                   |",
+                "|               Computer won on time.               |",
+                "|                                                   |",
+                "|          Caught       White  Gray Black           |",
+                "|          Alice            0     0     0           |",
+                "|          Computer         0     0     0           |",
+                "|                                                   |",
+                "|                 Moves played: 12                  |",
+                "|                                                   |",
+                "|      r: rematch  s: save the record  q: quit      |",
+                "+---------------------------------------------------+",
+            ]
+        );
+    }
+
+    #[test]
+    fn result_names_the_win_condition() {
+        let players = ["Carol".to_string(), "Dave".to_string()];
+        let win_condition = Rules::Standard.win_condition();
+        let result = GameResult {
+            winner: Some(Player::Bob),
+            reason: EndReason::WinCondition,
+        };
+
+        let scores = [MarbleCount::new(1, 1, 1), MarbleCount::new(0, 5, 2)];
+        assert_eq!(
+            describe_result(result, &players, scores, win_condition),
+            "Dave won by catching 5 gray marbles."
+        );
+        let scores = [MarbleCount::new(1, 1, 1), MarbleCount::new(3, 3, 3)];
+        assert_eq!(
+            describe_result(result, &players, scores, win_condition),
+            "Dave won by catching 3 marbles of each color."
+        );
+    }
+
+    #[test]
+    fn game_over_summary_with_a_long_name() {
+        let app = App::new(BoardKind::Rings37);
+        let mut renderer = renderer(COLUMNS, ROWS);
+        let render_data = RenderData {
+            players: ["Alice".to_string(), "C".repeat(80)],
+            summary: Some(GameSummary {
+                result: GameResult {
+                    winner: Some(Player::Bob),
+                    reason: EndReason::Resignation,
+                },
+                moves: 3,
+                can_rematch: false,
+                can_take_back: false,
+            }),
+            ..render_data(&app)
+        };
+        renderer.render_game_over(&render_data).unwrap();
+
+        let lines = region(&renderer, 15, 12, 53, 7);
+        assert_eq!(lines[2], format!("|{}|", "C".repeat(51)));
+        assert_eq!(
+            lines[6],
+            "|          CCCCCCCCCCC      0     0     0           |"
+        );
+    }
+
+    #[test]
+    fn compact_layout_leaves_out_the_panels() {
+        let app = App::new(BoardKind::Rings37);
+        let mut renderer = renderer(60, 36);
+        renderer.draw(&render_data(&app)).unwrap();
+
+        let lines = renderer.canvas.lines();
+        assert_eq!(
+            lines[..23],
+            [
+                "",
+                "  +-[ Board ]-------------------------------------------+",
+                "  |                                                     |",
+                "  |  .   .   .   .   .   .   .   .   .                  |",
+                "  |                                                     |",
+                "  |    .   .   .   .   .   .   .   .   .                |",
+                "  |                                                     |",
+                "  |      .   .   .   O   O   O   O   .   .              |",
+                "  |                                                     |",
+                "  |        .   .   O   O   O   O   O   .   .            |",
+                "  |                                                     |",
+                "  |          .   O   O   O   O   O   O   .   .          |",
+                "  |                                                     |",
+                "  |            O   O   O   O   O   O   O   .   .        |",
+                "  |                                                     |",
+                "  |              O   O   O   O   O   O   .   .   .      |",
+                "  |                                                     |",
+                "  |                O   O   O   O   O   .   .   .   .    |",
+                "  |                                                     |",
+                "  |                  O   O   O   O   .   .   .   .   .  |",
+                "  |                                                     |",
+                "  +-----------------------------------------------------+",
+                "",
+            ]
+        );
+        assert!(lines[23..].iter().all(String::is_empty));
+    }
+
+    #[test]
+    fn too_small_terminal() {
+        let app = App::new(BoardKind::Rings37);
+        let mut renderer = renderer(70, 10);
+        renderer.draw(&render_data(&app)).unwrap();
+
+        assert_eq!(
+            renderer.canvas.lines()[3..5],
+            [
+                "                 The terminal is too small to play.",
+                "    Please enlarge it to at least 57x35, or press Ctrl+C to quit.",
+            ]
+        );
+    }
 }
@@ -0,0 +1,128 @@
+use crossterm::style::ContentStyle;
+
+use crate::error;
+
+use super::canvas::Canvas;
+
+/// A cell of a [`Buffer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub symbol: char,
+    pub style: ContentStyle,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            symbol: ' ',
+            style: ContentStyle::new(),
+        }
+    }
+}
+
+/// A canvas kept in memory, whose cells can be compared with what is
+/// expected. Whatever is written beyond its edges is cut off.
+#[derive(Debug, Clone)]
+pub struct Buffer {
+    columns: u16,
+    rows: u16,
+    cells: Vec<Cell>,
+    ascii: bool,
+}
+
+impl Buffer {
+    pub fn new(columns: u16, rows: u16) -> Self {
+        Self {
+            columns,
+            rows,
+            cells: vec![Cell::default(); usize::from(columns) * usize::from(rows)],
+            ascii: false,
+        }
+    }
+
+    pub fn cell(&self, x: u16, y: u16) -> Cell {
+        self.cells[self.index(x, y)]
+    }
+
+    /// The symbols of every row, without the blanks at their ends.
+    pub fn lines(&self) -> Vec<String> {
+        self.cells
+            .chunks(usize::from(self.columns))
+            .map(|row| {
+                let line = row.iter().map(|cell| cell.symbol).collect::<String>();
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    fn index(&self, x: u16, y: u16) -> usize {
+        usize::from(y) * usize::from(self.columns) + usize::from(x)
+    }
+}
+
+impl Canvas for Buffer {
+    fn size(&self) -> (u16, u16) {
+        (self.columns, self.rows)
+    }
+
+    fn print(&mut self, x: u16, y: u16, text: &str, style: ContentStyle) -> error::Result<()> {
+        if y >= self.rows {
+            return Ok(());
+        }
+
+        for (column, symbol) in (x..self.columns).zip(text.chars()) {
+            let idx = self.index(column, y);
+            self.cells[idx] = Cell { symbol, style };
+        }
+
+        Ok(())
+    }
+
+    fn clear_line(&mut self, row: u16) -> error::Result<()> {
+        if row < self.rows {
+            let start = self.index(0, row);
+            self.cells[start..start + usize::from(self.columns)].fill(Cell::default());
+        }
+
+        Ok(())
+    }
+
+    fn clear_all(&mut self) -> error::Result<()> {
+        self.cells.fill(Cell::default());
+        Ok(())
+    }
+
+    fn flush(&mut self) -> error::Result<()> {
+        Ok(())
+    }
+
+    fn is_ascii(&self) -> bool {
+        self.ascii
+    }
+
+    fn set_ascii(&mut self, ascii: bool) {
+        self.ascii = ascii;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::style::Stylize;
+
+    use super::*;
+
+    #[test]
+    fn print_is_cut_off_at_the_edges() {
+        let mut buffer = Buffer::new(6, 2);
+        buffer.print_styled(3, 0, &"abcdef".bold()).unwrap();
+        buffer.print(0, 1, "xy", ContentStyle::new()).unwrap();
+        buffer.print(0, 2, "lost", ContentStyle::new()).unwrap();
+
+        assert_eq!(buffer.lines(), ["   abc", "xy"]);
+        assert_eq!(buffer.cell(3, 0).style, *"a".bold().style());
+        assert_eq!(buffer.cell(1, 1).style, ContentStyle::new());
+
+        buffer.clear_line(0).unwrap();
+        assert_eq!(buffer.lines(), ["", "xy"]);
+    }
+}
@@ -0,0 +1,51 @@
+use std::fmt::Display;
+
+use crossterm::style::{ContentStyle, StyledContent};
+
+use crate::error;
+
+use super::rect::{FrameChars, ASCII_FRAME, HEAVY_FRAME};
+
+/// Cells which shapes are drawn on, like the terminal.
+pub trait Canvas {
+    /// Columns and rows of the whole canvas.
+    fn size(&self) -> (u16, u16);
+
+    /// Write `text` from the cell at `(x, y)` to the right, in `style`.
+    fn print(&mut self, x: u16, y: u16, text: &str, style: ContentStyle) -> error::Result<()>;
+
+    /// Blank `row` all the way across.
+    fn clear_line(&mut self, row: u16) -> error::Result<()>;
+
+    /// Blank every cell, as after the size changed.
+    fn clear_all(&mut self) -> error::Result<()>;
+
+    /// Show what has been drawn since the last flush.
+    fn flush(&mut self) -> error::Result<()>;
+
+    /// Whether nothing but 7-bit ASCII can be shown.
+    fn is_ascii(&self) -> bool;
+
+    fn set_ascii(&mut self, ascii: bool);
+
+    fn frame_chars(&self) -> FrameChars {
+        if self.is_ascii() {
+            ASCII_FRAME
+        } else {
+            HEAVY_FRAME
+        }
+    }
+
+    /// Write `content` from the cell at `(x, y)`, in its own style.
+    fn print_styled<D: Display>(
+        &mut self,
+        x: u16,
+        y: u16,
+        content: &StyledContent<D>,
+    ) -> error::Result<()>
+    where
+        Self: Sized,
+    {
+        self.print(x, y, &content.content().to_string(), *content.style())
+    }
+}
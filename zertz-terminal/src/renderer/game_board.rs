@@ -1,29 +1,30 @@
 use std::ops::{Index, IndexMut};
 
-use crossterm::{
-    cursor::MoveTo,
-    queue,
-    style::{ContentStyle, Print, SetStyle, Stylize},
-};
+use crossterm::style::{ContentStyle, Stylize};
 use zertz_core::{
     board::*,
-    coordinate::{Coordinate as CoreCoordinate, CoordinateIter},
+    coordinate::{Coordinate as CoreCoordinate, CoordinateIter, Direction},
 };
 
-use super::{shape::Shape, terminal::Terminal};
-use crate::{coordinate::Coordinate, error};
+use super::{canvas::Canvas, shape::Shape};
+use crate::{coordinate::Coordinate, error, theme::Theme};
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct VisualRing {
     pub kind: Ring,
     pub focused: bool,
     pub selected: bool,
+    /// A ring on its way out, in an animation.
+    pub faded: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct GameBoard {
     board: [VisualRing; 81],
     origin: Coordinate,
+    /// Ring under the keyboard cursor.
+    cursor: CoreCoordinate,
+    theme: Theme,
     style: ContentStyle,
 }
 
@@ -37,9 +38,17 @@ impl GameBoard {
 
             tmp
         };
+        // The cursor starts around the middle of the board.
+        let rings = CoordinateIter::new()
+            .filter(|coord| board[usize::from(*coord)].kind != Ring::Empty)
+            .collect::<Vec<_>>();
+        let cursor = rings.get(rings.len() / 2).copied().unwrap_or_default();
+
         Self {
             board,
             origin: Coordinate::new(x, y),
+            cursor,
+            theme: Theme::default(),
             style: ContentStyle::new(),
         }
     }
@@ -49,6 +58,43 @@ impl GameBoard {
             self.board[idx].kind = ring;
         }
     }
+
+    /// Move the whole board, which is placed by the layout of the renderer.
+    pub fn set_origin(&mut self, origin: Coordinate) {
+        self.origin = origin;
+    }
+
+    /// Draw with `theme`, which is chosen by the renderer.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    #[inline]
+    pub fn cursor(&self) -> CoreCoordinate {
+        self.cursor
+    }
+
+    pub fn set_cursor(&mut self, coord: CoreCoordinate) {
+        self.cursor = coord;
+    }
+
+    /// Move the cursor to the next ring towards `direction`, passing over
+    /// removed rings. It stays where it is if there is no ring that way.
+    pub fn move_cursor(&mut self, direction: Direction) -> bool {
+        let mut coord = self.cursor;
+        while let Some(next) = coord
+            .adjacent(direction)
+            .filter(|next| next.x < 9 && next.y < 9)
+        {
+            coord = next;
+            if self[coord].kind != Ring::Empty {
+                self.cursor = coord;
+                return true;
+            }
+        }
+
+        false
+    }
 }
 
 impl Stylize for GameBoard {
@@ -60,32 +106,60 @@ impl Stylize for GameBoard {
 }
 
 impl Shape for GameBoard {
-    fn draw(&self, terminal: &mut Terminal) -> error::Result<()> {
+    fn draw<C: Canvas>(&self, canvas: &mut C) -> error::Result<()> {
         for coord in CoordinateIter::new() {
             let drawing = {
                 let VisualRing {
                     kind,
                     focused,
                     selected,
+                    faded,
                 } = self.board[usize::from(coord)];
+                let Theme {
+                    ring,
+                    removed,
+                    target,
+                    focused: focused_color,
+                    selected: selected_color,
+                    ..
+                } = self.theme;
                 match (kind, focused, selected) {
-                    (Ring::Empty, _, _) => ".".reset(),
-                    (Ring::Vacant, false, true) => "O".yellow().bold(),
-                    (Ring::Vacant, true, false) => "X".green().bold(),
-                    (Ring::Vacant, true, true) => "X".yellow().bold(),
-                    (Ring::Vacant, _, _) => "O".bold(),
-                    (Ring::Occupied(Marble::White), _, _) => "@".bold().black().on_white(),
-                    (Ring::Occupied(Marble::Gray), _, _) => "@".bold().white().on_dark_grey(),
-                    (Ring::Occupied(Marble::Black), _, _) => "@".bold().white().on_black(),
+                    _ if faded => ring.dim(),
+                    (Ring::Empty, _, _) => removed.reset(),
+                    (Ring::Vacant, false, true) => ring.with(selected_color).bold(),
+                    (Ring::Vacant, true, false) => target.with(focused_color).bold(),
+                    (Ring::Vacant, true, true) => target.with(selected_color).bold(),
+                    (Ring::Vacant, _, _) => ring.bold(),
+                    (Ring::Occupied(marble), _, true) => {
+                        self.theme.highlighted_marble(marble, selected_color)
+                    }
+                    (Ring::Occupied(marble), true, false) => {
+                        self.theme.highlighted_marble(marble, focused_color)
+                    }
+                    (Ring::Occupied(marble), _, _) => self.theme.marble(marble),
                 }
             };
 
+            let drawing = if coord == self.cursor {
+                drawing.reverse()
+            } else {
+                drawing
+            };
+
+            // The style of the board is under the style of each ring.
+            let ring_style = drawing.style();
+            let style = ContentStyle {
+                foreground_color: ring_style.foreground_color.or(self.style.foreground_color),
+                background_color: ring_style.background_color.or(self.style.background_color),
+                underline_color: ring_style.underline_color.or(self.style.underline_color),
+                attributes: self.style.attributes | ring_style.attributes,
+            };
             let render_coord = Coordinate::from_core_coord(coord, self.origin);
-            queue!(
-                terminal.stdout,
-                SetStyle(self.style),
-                MoveTo(render_coord.x, render_coord.y),
-                Print(drawing),
+            canvas.print(
+                render_coord.x,
+                render_coord.y,
+                drawing.content().encode_utf8(&mut [0; 4]),
+                style,
             )?;
         }
 
@@ -118,3 +192,74 @@ impl IndexMut<CoreCoordinate> for GameBoard {
         &mut self.board[usize::from(index)]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zertz_core::{app::App, game::Move};
+
+    use super::*;
+    use crate::renderer::buffer::Buffer;
+
+    fn draw(game_board: GameBoard) -> Vec<String> {
+        let mut buffer = Buffer::new(49, 17);
+        buffer.set_ascii(true);
+        game_board.draw(&mut buffer).unwrap();
+        buffer.lines()
+    }
+
+    #[test]
+    fn rings_of_a_new_board() {
+        let app = App::new(BoardKind::Rings37);
+        let mut game_board = GameBoard::new(&app.get_current_board(), 16, 16);
+        game_board.set_theme(Theme::default().to_ascii());
+
+        assert_eq!(
+            draw(game_board),
+            [
+                ".   .   .   .   .   .   .   .   .",
+                "",
+                "  .   .   .   .   .   .   .   .   .",
+                "",
+                "    .   .   .   O   O   O   O   .   .",
+                "",
+                "      .   .   O   O   O   O   O   .   .",
+                "",
+                "        .   O   O   O   O   O   O   .   .",
+                "",
+                "          O   O   O   O   O   O   O   .   .",
+                "",
+                "            O   O   O   O   O   O   .   .   .",
+                "",
+                "              O   O   O   O   O   .   .   .   .",
+                "",
+                "                O   O   O   O   .   .   .   .   .",
+            ]
+        );
+    }
+
+    #[test]
+    fn marbles_and_removed_rings() {
+        let mut app = App::new(BoardKind::Rings37);
+        let mv = app.game().legal_moves()[0];
+        app.play_move(mv).unwrap();
+        let Move::Put { put_coord, .. } = mv else {
+            unreachable!();
+        };
+        let mut game_board = GameBoard::new(&app.get_current_board(), 16, 16);
+        let theme = Theme::default().to_ascii();
+        game_board.set_theme(theme);
+        game_board[put_coord].focused = true;
+
+        let mut buffer = Buffer::new(49, 17);
+        game_board.draw(&mut buffer).unwrap();
+        assert_eq!(
+            buffer.lines()[16],
+            "                W   .   O   O   .   .   .   .   ."
+        );
+        let marble = Coordinate::from_core_coord(put_coord, Coordinate::new(16, 16));
+        assert_eq!(
+            buffer.cell(marble.x, marble.y).style.foreground_color,
+            Some(theme.focused)
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use crossterm::style::{ContentStyle, Stylize};
+
+use crate::coordinate::Coordinate;
+use crate::error;
+
+use super::{
+    canvas::Canvas,
+    shape::Shape,
+    titlebox::{TitleBox, TitleLocation},
+};
+
+const DISMISS_TEXT: &str = "Press any key to continue.";
+// Longer lists scroll with the highlighted line.
+const MAX_LISTED_LINES: usize = 10;
+
+/// A modal box with a message, which hides everything under it.
+#[derive(Debug, Clone)]
+pub struct MessageBox {
+    title_box: TitleBox,
+    origin: Coordinate,
+    width: u16,
+    height: u16,
+    lines: Vec<String>,
+    // Index of the first line shown, when a list scrolls.
+    first: usize,
+    highlighted: Option<usize>,
+    footer: String,
+}
+
+impl MessageBox {
+    /// A box of `width` around `center`, which is as tall as the message.
+    pub fn new(center: Coordinate, width: u16, title: impl ToString, message: &str) -> Self {
+        let lines = wrap(message, width.saturating_sub(4) as usize);
+        Self::with_lines(center, width, title, lines, None)
+    }
+
+    /// A box listing `items`, where the `selected` one is highlighted.
+    pub fn list(
+        center: Coordinate,
+        width: u16,
+        title: impl ToString,
+        items: &[String],
+        selected: usize,
+    ) -> Self {
+        let first = (selected + 1).saturating_sub(MAX_LISTED_LINES);
+        let lines = items
+            .iter()
+            .skip(first)
+            .take(MAX_LISTED_LINES)
+            .map(|item| {
+                item.chars()
+                    .take(width.saturating_sub(4) as usize)
+                    .collect()
+            })
+            .collect();
+        Self {
+            first,
+            ..Self::with_lines(center, width, title, lines, Some(selected - first))
+        }
+    }
+
+    /// Index of the line under the screen position `(column, row)`, counted
+    /// as the items of [`MessageBox::list`].
+    pub fn line_at(&self, column: u16, row: u16) -> Option<usize> {
+        let is_inside = (self.origin.x + 1..self.origin.x + self.width.saturating_sub(1))
+            .contains(&column)
+            && (self.origin.y + 2..self.origin.y + 2 + self.lines.len() as u16).contains(&row);
+        is_inside.then(|| self.first + (row - self.origin.y - 2) as usize)
+    }
+
+    /// Replace the last line, which tells how to dismiss the box.
+    pub fn footer(mut self, footer: impl ToString) -> Self {
+        self.footer = footer.to_string();
+        self
+    }
+
+    fn with_lines(
+        center: Coordinate,
+        width: u16,
+        title: impl ToString,
+        lines: Vec<String>,
+        highlighted: Option<usize>,
+    ) -> Self {
+        let height = lines.len() as u16 + 6;
+        let origin = Coordinate::new(
+            center.x.saturating_sub(width / 2),
+            center.y.saturating_sub(height / 2),
+        );
+        let title_box = TitleBox::new(origin.x, origin.y, width, height, title)
+            .alignment(TitleLocation::TopCenter);
+
+        Self {
+            title_box,
+            origin,
+            width,
+            height,
+            lines,
+            first: 0,
+            highlighted,
+            footer: DISMISS_TEXT.to_string(),
+        }
+    }
+}
+
+impl Stylize for MessageBox {
+    type Styled = Self;
+
+    fn stylize(self) -> Self::Styled {
+        self
+    }
+}
+
+impl Shape for MessageBox {
+    fn draw<C: Canvas>(&self, canvas: &mut C) -> error::Result<()> {
+        let blank = " ".repeat(self.width.saturating_sub(2) as usize);
+        for row in 1..self.height.saturating_sub(1) {
+            canvas.print(
+                self.origin.x + 1,
+                self.origin.y + row,
+                &blank,
+                ContentStyle::new(),
+            )?;
+        }
+        self.title_box.draw(canvas)?;
+
+        for (row, line) in self.lines.iter().enumerate() {
+            let (x, y) = (self.origin.x + 2, self.origin.y + 2 + row as u16);
+            if self.highlighted == Some(row) {
+                canvas.print_styled(x, y, &line.as_str().reverse())?;
+            } else {
+                canvas.print(x, y, line, ContentStyle::new())?;
+            }
+        }
+        canvas.print(
+            self.origin.x + 2,
+            self.origin.y + 3 + self.lines.len() as u16,
+            &self.footer,
+            ContentStyle::new(),
+        )?;
+
+        Ok(())
+    }
+}
+
+impl AsRef<ContentStyle> for MessageBox {
+    fn as_ref(&self) -> &ContentStyle {
+        self.title_box.as_ref()
+    }
+}
+
+impl AsMut<ContentStyle> for MessageBox {
+    fn as_mut(&mut self) -> &mut ContentStyle {
+        self.title_box.as_mut()
+    }
+}
+
+// Break `text` into lines no longer than `width`, between words.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.len() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
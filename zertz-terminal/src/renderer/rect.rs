@@ -1,18 +1,37 @@
-use crossterm::{
-    cursor::MoveTo,
-    queue,
-    style::{ContentStyle, Print, ResetColor, SetStyle, Stylize},
-};
+use crossterm::style::{ContentStyle, Stylize};
 
-use super::{shape::Shape, terminal::Terminal};
+use super::{canvas::Canvas, shape::Shape};
 use crate::error;
 
-pub(super) const HORIZONTAL_LINE: char = '\u{2501}';
-pub(super) const VERTICAL_LINE: char = '\u{2503}';
-pub(super) const TOP_LEFT_CORNER: char = '\u{250F}';
-pub(super) const BOTTOM_LEFT_CORNER: char = '\u{2517}';
-pub(super) const TOP_RIGHT_CORNER: char = '\u{2513}';
-pub(super) const BOTTOM_RIGHT_CORNER: char = '\u{251B}';
+/// Characters of the lines and corners of a frame.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameChars {
+    pub horizontal: char,
+    pub vertical: char,
+    pub top_left: char,
+    pub bottom_left: char,
+    pub top_right: char,
+    pub bottom_right: char,
+}
+
+pub const HEAVY_FRAME: FrameChars = FrameChars {
+    horizontal: '\u{2501}',
+    vertical: '\u{2503}',
+    top_left: '\u{250F}',
+    bottom_left: '\u{2517}',
+    top_right: '\u{2513}',
+    bottom_right: '\u{251B}',
+};
+
+/// For terminals which show only 7-bit ASCII.
+pub const ASCII_FRAME: FrameChars = FrameChars {
+    horizontal: '-',
+    vertical: '|',
+    top_left: '+',
+    bottom_left: '+',
+    top_right: '+',
+    bottom_right: '+',
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Rect {
@@ -44,41 +63,25 @@ impl Stylize for Rect {
 }
 
 impl Shape for Rect {
-    fn draw(&self, terminal: &mut Terminal) -> error::Result<()> {
-        queue!(
-            terminal.stdout,
-            SetStyle(self.style),
-            MoveTo(self.x, self.y),
-            Print(TOP_LEFT_CORNER),
-            MoveTo(self.x + self.width, self.y),
-            Print(TOP_RIGHT_CORNER),
-            MoveTo(self.x, self.y + self.height),
-            Print(BOTTOM_LEFT_CORNER),
-            MoveTo(self.x + self.width, self.y + self.height),
-            Print(BOTTOM_RIGHT_CORNER),
-        )?;
+    fn draw<C: Canvas>(&self, canvas: &mut C) -> error::Result<()> {
+        let frame = canvas.frame_chars();
+        let (right, bottom) = (self.x + self.width, self.y + self.height);
+        let mut put = |x: u16, y: u16, symbol: char| {
+            canvas.print(x, y, symbol.encode_utf8(&mut [0; 4]), self.style)
+        };
 
+        put(self.x, self.y, frame.top_left)?;
+        put(right, self.y, frame.top_right)?;
+        put(self.x, bottom, frame.bottom_left)?;
+        put(right, bottom, frame.bottom_right)?;
         for i in 1..self.width {
-            queue!(
-                terminal.stdout,
-                SetStyle(self.style),
-                MoveTo(self.x + i, self.y),
-                Print(HORIZONTAL_LINE),
-                MoveTo(self.x + i, self.y + self.height),
-                Print(HORIZONTAL_LINE),
-            )?;
+            put(self.x + i, self.y, frame.horizontal)?;
+            put(self.x + i, bottom, frame.horizontal)?;
         }
         for i in 1..self.height {
-            queue!(
-                terminal.stdout,
-                SetStyle(self.style),
-                MoveTo(self.x, self.y + i),
-                Print(VERTICAL_LINE),
-                MoveTo(self.x + self.width, self.y + i),
-                Print(VERTICAL_LINE),
-            )?;
+            put(self.x, self.y + i, frame.vertical)?;
+            put(right, self.y + i, frame.vertical)?;
         }
-        queue!(terminal.stdout, ResetColor)?;
 
         Ok(())
     }
@@ -1,6 +1,6 @@
-use super::terminal::Terminal;
+use super::canvas::Canvas;
 use crate::error;
 
 pub trait Shape {
-    fn draw(&self, terminal: &mut Terminal) -> error::Result<()>;
+    fn draw<C: Canvas>(&self, canvas: &mut C) -> error::Result<()>;
 }
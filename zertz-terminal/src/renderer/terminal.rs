@@ -4,16 +4,22 @@ use std::time::Duration;
 use crossterm::{
     cursor::MoveTo,
     event::{poll, read, Event},
-    execute,
+    execute, queue,
+    style::{ContentStyle, PrintStyledContent},
     terminal::{size, Clear, ClearType},
 };
 
 use crate::error;
 
+use super::canvas::Canvas;
+
+/// The canvas of the terminal, drawn with crossterm.
 pub struct Terminal {
     pub(super) stdout: Stdout,
     pub(super) width: u16,
     pub(super) height: u16,
+    /// Only 7-bit ASCII is printed.
+    ascii: bool,
 }
 
 impl Terminal {
@@ -22,11 +28,17 @@ impl Terminal {
 
         Ok(Self {
             stdout: io::stdout(),
-            width: width - 1,
-            height: height - 1,
+            width: width.saturating_sub(1),
+            height: height.saturating_sub(1),
+            ascii: false,
         })
     }
 
+    pub fn resize(&mut self, columns: u16, rows: u16) {
+        self.width = columns.saturating_sub(1);
+        self.height = rows.saturating_sub(1);
+    }
+
     pub fn read(&self) -> error::Result<Event> {
         Ok(read()?)
     }
@@ -38,8 +50,22 @@ impl Terminal {
     pub fn clear(&mut self) -> error::Result<()> {
         Ok(execute!(self.stdout, Clear(ClearType::Purge))?)
     }
+}
+
+impl Canvas for Terminal {
+    fn size(&self) -> (u16, u16) {
+        (self.width + 1, self.height + 1)
+    }
+
+    fn print(&mut self, x: u16, y: u16, text: &str, style: ContentStyle) -> error::Result<()> {
+        Ok(queue!(
+            self.stdout,
+            MoveTo(x, y),
+            PrintStyledContent(style.apply(text))
+        )?)
+    }
 
-    pub fn clear_line(&mut self, row: u16) -> error::Result<()> {
+    fn clear_line(&mut self, row: u16) -> error::Result<()> {
         Ok(execute!(
             self.stdout,
             MoveTo(0, row),
@@ -47,7 +73,19 @@ impl Terminal {
         )?)
     }
 
-    pub fn flush(&mut self) -> error::Result<()> {
+    fn clear_all(&mut self) -> error::Result<()> {
+        Ok(execute!(self.stdout, Clear(ClearType::All))?)
+    }
+
+    fn flush(&mut self) -> error::Result<()> {
         Ok(self.stdout.flush()?)
     }
+
+    fn is_ascii(&self) -> bool {
+        self.ascii
+    }
+
+    fn set_ascii(&mut self, ascii: bool) {
+        self.ascii = ascii;
+    }
 }
@@ -1,14 +1,10 @@
 use std::string::ToString;
 
-use crossterm::{
-    cursor::MoveTo,
-    queue,
-    style::{ContentStyle, Print, ResetColor, SetStyle, Stylize},
-};
+use crossterm::style::{ContentStyle, Stylize};
 
 use crate::error;
 
-use super::{rect::*, shape::Shape, terminal::Terminal};
+use super::{canvas::Canvas, rect::*, shape::Shape};
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, Default)]
@@ -41,7 +37,6 @@ impl TitleBox {
         }
     }
 
-    #[allow(dead_code)]
     pub fn alignment(mut self, title_location: TitleLocation) -> Self {
         self.title_location = title_location;
         self
@@ -57,196 +52,29 @@ impl Stylize for TitleBox {
 }
 
 impl Shape for TitleBox {
-    fn draw(&self, terminal: &mut Terminal) -> error::Result<()> {
+    // The title is written over the frame.
+    fn draw<C: Canvas>(&self, canvas: &mut C) -> error::Result<()> {
+        let Rect {
+            x,
+            y,
+            width,
+            height,
+            style,
+        } = self.rect;
         let title_len = self.title.len() as u16;
 
-        queue!(
-            terminal.stdout,
-            SetStyle(self.rect.style),
-            MoveTo(self.rect.x, self.rect.y),
-            Print(TOP_LEFT_CORNER),
-            MoveTo(self.rect.x + self.rect.width, self.rect.y),
-            Print(TOP_RIGHT_CORNER),
-            MoveTo(self.rect.x, self.rect.y + self.rect.height),
-            Print(BOTTOM_LEFT_CORNER),
-            MoveTo(
-                self.rect.x + self.rect.width,
-                self.rect.y + self.rect.height
-            ),
-            Print(BOTTOM_RIGHT_CORNER),
-        )?;
-
-        match self.title_location {
-            TitleLocation::TopLeft => {
-                queue!(
-                    terminal.stdout,
-                    SetStyle(self.rect.style),
-                    MoveTo(self.rect.x + 1, self.rect.y),
-                    Print(HORIZONTAL_LINE),
-                    MoveTo(self.rect.x + 2, self.rect.y),
-                    Print(&self.title)
-                )?;
-                for i in (2 + title_len)..self.rect.width {
-                    queue!(
-                        terminal.stdout,
-                        SetStyle(self.rect.style),
-                        MoveTo(self.rect.x + i, self.rect.y),
-                        Print(HORIZONTAL_LINE),
-                    )?;
-                }
-            }
-            TitleLocation::TopCenter => {
-                for i in 1..((self.rect.width - title_len) / 2) {
-                    queue!(
-                        terminal.stdout,
-                        SetStyle(self.rect.style),
-                        MoveTo(self.rect.x + i, self.rect.y),
-                        Print(HORIZONTAL_LINE),
-                    )?;
-                }
-                queue!(
-                    terminal.stdout,
-                    SetStyle(self.rect.style),
-                    MoveTo(
-                        self.rect.x + ((self.rect.width - title_len) / 2),
-                        self.rect.y
-                    ),
-                    Print(&self.title)
-                )?;
-                for i in ((self.rect.width + title_len) / 2)..self.rect.width {
-                    queue!(
-                        terminal.stdout,
-                        SetStyle(self.rect.style),
-                        MoveTo(self.rect.x + i, self.rect.y),
-                        Print(HORIZONTAL_LINE),
-                    )?;
-                }
-            }
-            TitleLocation::TopRight => {
-                for i in 1..(self.rect.width - 1 - title_len) {
-                    queue!(
-                        terminal.stdout,
-                        SetStyle(self.rect.style),
-                        MoveTo(self.rect.x + i, self.rect.y),
-                        Print(HORIZONTAL_LINE),
-                    )?;
-                }
-                queue!(
-                    terminal.stdout,
-                    SetStyle(self.rect.style),
-                    MoveTo(self.rect.x + self.rect.width - 1 - title_len, self.rect.y),
-                    Print(&self.title),
-                    MoveTo(self.rect.x + self.rect.width - 1, self.rect.y),
-                    Print(HORIZONTAL_LINE),
-                )?;
-            }
-            _ => {
-                for i in 1..self.rect.width {
-                    queue!(
-                        terminal.stdout,
-                        SetStyle(self.rect.style),
-                        MoveTo(self.rect.x + i, self.rect.y),
-                        Print(HORIZONTAL_LINE),
-                    )?;
-                }
-            }
-        }
-
-        for i in 1..self.rect.height {
-            queue!(
-                terminal.stdout,
-                SetStyle(self.rect.style),
-                MoveTo(self.rect.x, self.rect.y + i),
-                Print(VERTICAL_LINE),
-                MoveTo(self.rect.x + self.rect.width, self.rect.y + i),
-                Print(VERTICAL_LINE),
-            )?;
-        }
-
-        match self.title_location {
-            TitleLocation::BottomLeft => {
-                queue!(
-                    terminal.stdout,
-                    SetStyle(self.rect.style),
-                    MoveTo(self.rect.x + 1, self.rect.y + self.rect.height),
-                    Print(HORIZONTAL_LINE),
-                    MoveTo(self.rect.x + 2, self.rect.y + self.rect.height),
-                    Print(&self.title)
-                )?;
-                for i in (2 + title_len)..self.rect.width {
-                    queue!(
-                        terminal.stdout,
-                        SetStyle(self.rect.style),
-                        MoveTo(self.rect.x + i, self.rect.y + self.rect.height),
-                        Print(HORIZONTAL_LINE),
-                    )?;
-                }
-            }
-            TitleLocation::BottomCenter => {
-                for i in 1..((self.rect.width - title_len) / 2) {
-                    queue!(
-                        terminal.stdout,
-                        SetStyle(self.rect.style),
-                        MoveTo(self.rect.x + i, self.rect.y + self.rect.height),
-                        Print(HORIZONTAL_LINE),
-                    )?;
-                }
-                queue!(
-                    terminal.stdout,
-                    SetStyle(self.rect.style),
-                    MoveTo(
-                        self.rect.x + ((self.rect.width - title_len) / 2),
-                        self.rect.y + self.rect.height
-                    ),
-                    Print(&self.title)
-                )?;
-                for i in ((self.rect.width + title_len) / 2)..self.rect.width {
-                    queue!(
-                        terminal.stdout,
-                        SetStyle(self.rect.style),
-                        MoveTo(self.rect.x + i, self.rect.y + self.rect.height),
-                        Print(HORIZONTAL_LINE),
-                    )?;
-                }
-            }
-            TitleLocation::BottomRight => {
-                for i in 1..(self.rect.width - 1 - title_len) {
-                    queue!(
-                        terminal.stdout,
-                        SetStyle(self.rect.style),
-                        MoveTo(self.rect.x + i, self.rect.y + self.rect.height),
-                        Print(HORIZONTAL_LINE),
-                    )?;
-                }
-                queue!(
-                    terminal.stdout,
-                    SetStyle(self.rect.style),
-                    MoveTo(
-                        self.rect.x + self.rect.width - 1 - title_len,
-                        self.rect.y + self.rect.height
-                    ),
-                    Print(&self.title),
-                    MoveTo(
-                        self.rect.x + self.rect.width - 1,
-                        self.rect.y + self.rect.height
-                    ),
-                    Print(HORIZONTAL_LINE),
-                )?;
-            }
-            _ => {
-                for i in 1..self.rect.width {
-                    queue!(
-                        terminal.stdout,
-                        SetStyle(self.rect.style),
-                        MoveTo(self.rect.x + i, self.rect.y + self.rect.height),
-                        Print(HORIZONTAL_LINE),
-                    )?;
-                }
-            }
-        }
-        queue!(terminal.stdout, ResetColor)?;
-
-        Ok(())
+        let row = match self.title_location {
+            TitleLocation::TopLeft | TitleLocation::TopCenter | TitleLocation::TopRight => y,
+            _ => y + height,
+        };
+        let column = match self.title_location {
+            TitleLocation::TopLeft | TitleLocation::BottomLeft => x + 2,
+            TitleLocation::TopCenter | TitleLocation::BottomCenter => x + (width - title_len) / 2,
+            TitleLocation::TopRight | TitleLocation::BottomRight => x + width - 1 - title_len,
+        };
+
+        self.rect.draw(canvas)?;
+        canvas.print(column, row, &self.title, style)
     }
 }
 
@@ -261,3 +89,54 @@ impl AsMut<ContentStyle> for TitleBox {
         &mut self.rect.style
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::buffer::Buffer;
+
+    fn draw(title_box: TitleBox, ascii: bool) -> Vec<String> {
+        let mut buffer = Buffer::new(16, 4);
+        buffer.set_ascii(ascii);
+        title_box.draw(&mut buffer).unwrap();
+        buffer.lines()
+    }
+
+    #[test]
+    fn title_on_the_top_left() {
+        assert_eq!(
+            draw(TitleBox::new(0, 0, 14, 4, "[ Moves ]"), true),
+            [
+                "+-[ Moves ]--+",
+                "|            |",
+                "|            |",
+                "+------------+",
+            ]
+        );
+    }
+
+    #[test]
+    fn title_on_the_bottom_right() {
+        let title_box =
+            TitleBox::new(0, 0, 16, 3, "[ Moves ]").alignment(TitleLocation::BottomRight);
+        assert_eq!(
+            draw(title_box, false),
+            [
+                "┏━━━━━━━━━━━━━━┓",
+                "┃              ┃",
+                "┗━━━━[ Moves ]━┛",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn title_is_cut_to_the_width() {
+        let title_box =
+            TitleBox::new(1, 1, 10, 3, "[ Warning ]").alignment(TitleLocation::TopCenter);
+        assert_eq!(
+            draw(title_box, true),
+            ["", " +[ Warn--+", " |        |", " +--------+"]
+        );
+    }
+}
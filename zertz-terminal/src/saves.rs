@@ -0,0 +1,147 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use zertz_core::{
+    app::App,
+    board::BoardKind,
+    game::{GameState, Player},
+};
+
+use crate::error;
+
+/// Name of the save written when a game is left unfinished.
+pub const AUTOSAVE_NAME: &str = "autosave";
+
+/// What is shown about a saved game before it is loaded.
+#[derive(Debug, Clone)]
+pub struct SaveEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub board_kind: BoardKind,
+    pub modified: SystemTime,
+    pub moves: usize,
+    /// `None` if the game is over.
+    pub to_move: Option<Player>,
+}
+
+impl SaveEntry {
+    pub fn describe(&self) -> String {
+        let turn = match self.to_move {
+            Some(player) => format!("{player:?} to move"),
+            None => "finished".to_string(),
+        };
+
+        format!(
+            "{}  {} rings  {}  move {}, {turn}",
+            self.name,
+            self.board_kind,
+            format_date(self.modified),
+            self.moves + 1,
+        )
+    }
+}
+
+/// Every game saved in `dir`, the latest first. Files which are not saved
+/// games are left out.
+pub fn list(dir: &Path) -> error::Result<Vec<SaveEntry>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for file in fs::read_dir(dir)? {
+        let path = file?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let Ok(Ok(app)) = fs::read_to_string(&path).map(App::load) else {
+            continue;
+        };
+
+        entries.push(SaveEntry {
+            name: path
+                .file_stem()
+                .map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+            board_kind: app.get_current_board().kind,
+            modified: fs::metadata(&path)?.modified()?,
+            moves: app.moves().len(),
+            to_move: match app.get_game_state() {
+                GameState::GameEnd(_) => None,
+                _ => Some(app.game().current_player()),
+            },
+            path,
+        });
+    }
+    entries.sort_by_key(|entry| Reverse(entry.modified));
+
+    Ok(entries)
+}
+
+/// Where the save called `name` is kept in `dir`.
+pub fn path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{name}.json"))
+}
+
+/// Save `app` in `dir` under `name`, and return where it is.
+pub fn save(dir: &Path, name: &str, app: &App) -> error::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = path(dir, name);
+    fs::write(&path, app.save()?)?;
+
+    Ok(path)
+}
+
+pub fn load(path: &Path) -> error::Result<App> {
+    Ok(App::load(fs::read_to_string(path)?)?)
+}
+
+/// Whether `c` can be typed in the name of a save.
+pub fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_')
+}
+
+// `YYYY-MM-DD hh:mm` in UTC.
+fn format_date(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let (days, secs) = (secs / 86400, secs % 86400);
+
+    // Howard Hinnant's `civil_from_days`, for days after 1970-01-01.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        secs / 3600,
+        secs % 3600 / 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn format_known_dates() {
+        for (secs, date) in [
+            (0, "1970-01-01 00:00"),
+            (1_709_210_040, "2024-02-29 12:34"),
+            (946_684_740, "1999-12-31 23:59"),
+            (946_684_800, "2000-01-01 00:00"),
+        ] {
+            assert_eq!(format_date(UNIX_EPOCH + Duration::from_secs(secs)), date);
+        }
+    }
+}
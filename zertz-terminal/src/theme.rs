@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::Path;
+
+use clap::ValueEnum;
+use crossterm::style::{Color, StyledContent, Stylize};
+use serde::{Deserialize, Serialize};
+use zertz_core::board::Marble;
+
+use crate::error;
+
+/// How a marble is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarbleStyle {
+    pub glyph: char,
+    pub foreground: Color,
+    pub background: Color,
+}
+
+impl MarbleStyle {
+    const fn new(glyph: char, foreground: Color, background: Color) -> Self {
+        Self {
+            glyph,
+            foreground,
+            background,
+        }
+    }
+}
+
+/// Glyphs and colors of the board.
+///
+/// A theme file is the JSON of a theme, where whatever is left out is taken
+/// from the dark theme. Colors are named like `dark_grey`, `rgb_(230,159,0)`
+/// or `ansi_(208)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    pub white: MarbleStyle,
+    pub gray: MarbleStyle,
+    pub black: MarbleStyle,
+    /// A vacant ring.
+    pub ring: char,
+    /// Where a ring has been removed.
+    pub removed: char,
+    /// A vacant ring which can be chosen.
+    pub target: char,
+    /// Rings and marbles which can be chosen.
+    pub focused: Color,
+    /// The ring or marble chosen.
+    pub selected: Color,
+}
+
+impl Theme {
+    pub fn load(path: &Path) -> error::Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// The same theme with nothing but 7-bit ASCII, where marbles are told
+    /// apart by their letters.
+    pub fn to_ascii(self) -> Self {
+        let dark = Self::default();
+        let ascii_or = |glyph: char, default: char| {
+            if glyph.is_ascii() {
+                glyph
+            } else {
+                default
+            }
+        };
+
+        Self {
+            white: MarbleStyle {
+                glyph: 'W',
+                ..self.white
+            },
+            gray: MarbleStyle {
+                glyph: 'G',
+                ..self.gray
+            },
+            black: MarbleStyle {
+                glyph: 'B',
+                ..self.black
+            },
+            ring: ascii_or(self.ring, dark.ring),
+            removed: ascii_or(self.removed, dark.removed),
+            target: ascii_or(self.target, dark.target),
+            ..self
+        }
+    }
+
+    pub fn marble_style(&self, marble: Marble) -> MarbleStyle {
+        match marble {
+            Marble::White => self.white,
+            Marble::Gray => self.gray,
+            Marble::Black => self.black,
+        }
+    }
+
+    /// How a marble of each color is drawn, on the board and beside it.
+    pub fn marble(&self, marble: Marble) -> StyledContent<char> {
+        let style = self.marble_style(marble);
+        style
+            .glyph
+            .bold()
+            .with(style.foreground)
+            .on(style.background)
+    }
+
+    /// A marble which is focused or selected, with its background kept so its
+    /// color is still seen.
+    pub fn highlighted_marble(&self, marble: Marble, color: Color) -> StyledContent<char> {
+        let style = self.marble_style(marble);
+        style.glyph.bold().with(color).on(style.background)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        ThemePreset::Dark.theme()
+    }
+}
+
+/// Themes which come with the game.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ThemePreset {
+    /// For a dark background.
+    #[default]
+    Dark,
+    /// For a light background.
+    Light,
+    /// Bright highlights, which stand out from every marble.
+    HighContrast,
+    /// Marbles told apart by their letters as well, with highlights in blue
+    /// and orange.
+    ColourBlind,
+}
+
+impl ThemePreset {
+    pub fn theme(self) -> Theme {
+        let dark = Theme {
+            white: MarbleStyle::new('@', Color::Black, Color::White),
+            gray: MarbleStyle::new('@', Color::White, Color::DarkGrey),
+            black: MarbleStyle::new('@', Color::White, Color::Black),
+            ring: 'O',
+            removed: '.',
+            target: 'X',
+            focused: Color::Green,
+            selected: Color::Yellow,
+        };
+
+        match self {
+            Self::Dark => dark,
+            Self::Light => Theme {
+                white: MarbleStyle::new('@', Color::Black, Color::Grey),
+                focused: Color::DarkGreen,
+                selected: Color::DarkMagenta,
+                ..dark
+            },
+            Self::HighContrast => Theme {
+                white: MarbleStyle::new('@', Color::Black, Color::White),
+                gray: MarbleStyle::new('@', Color::Black, Color::Grey),
+                black: MarbleStyle::new('@', Color::White, Color::Black),
+                target: '+',
+                focused: Color::Cyan,
+                selected: Color::Magenta,
+                ..dark
+            },
+            // Blue and orange of the Okabe-Ito palette.
+            Self::ColourBlind => Theme {
+                white: MarbleStyle::new('W', Color::Black, Color::White),
+                gray: MarbleStyle::new('G', Color::White, Color::DarkGrey),
+                black: MarbleStyle::new('B', Color::White, Color::Black),
+                target: '+',
+                focused: Color::Rgb {
+                    r: 86,
+                    g: 180,
+                    b: 233,
+                },
+                selected: Color::Rgb {
+                    r: 230,
+                    g: 159,
+                    b: 0,
+                },
+                ..dark
+            },
+        }
+    }
+}
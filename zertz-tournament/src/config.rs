@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+use zertz_core::{board::BoardKind, game::Rules, search::engine::SearchLimits};
+
+use crate::error::{self, ZertzTournamentError};
+
+const DEFAULT_HASH_SIZE_MB: usize = 16;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Every player meets every other player.
+    #[default]
+    RoundRobin,
+    /// Only the first player meets the others.
+    Gauntlet,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlayerConfig {
+    pub name: String,
+    /// Maximum search depth in plies.
+    pub depth: Option<u8>,
+    /// Maximum thinking time for a single move.
+    pub move_time_ms: Option<u64>,
+    /// Maximum number of searched nodes for a single move.
+    pub nodes: Option<u64>,
+    /// Path of an opening book made by `OpeningBook::save`.
+    pub book: Option<PathBuf>,
+    #[serde(default = "default_hash_size_mb")]
+    pub hash_mb: usize,
+}
+
+fn default_hash_size_mb() -> usize {
+    DEFAULT_HASH_SIZE_MB
+}
+
+impl PlayerConfig {
+    pub fn search_limits(&self) -> SearchLimits {
+        SearchLimits {
+            depth: self.depth,
+            move_time: self.move_time_ms.map(Duration::from_millis),
+            nodes: self.nodes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TournamentConfig {
+    pub players: Vec<PlayerConfig>,
+    pub mode: Mode,
+    pub board_kinds: Vec<BoardKind>,
+    pub rules: Vec<Rules>,
+    /// Number of games between two players for each board kind and rule set.
+    /// Colors are swapped after every game, so an even number is fair.
+    pub games_per_pair: usize,
+    /// Time on the clock of each player for a whole game. A player whose
+    /// clock runs out loses the game.
+    pub game_time_ms: Option<u64>,
+    /// Games which last longer than this are adjudicated as a draw.
+    pub max_plies: usize,
+    pub threads: usize,
+    pub output_dir: PathBuf,
+}
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        Self {
+            players: Vec::new(),
+            mode: Mode::default(),
+            board_kinds: vec![BoardKind::default()],
+            rules: vec![Rules::default()],
+            games_per_pair: 2,
+            game_time_ms: None,
+            max_plies: 200,
+            threads: 1,
+            output_dir: PathBuf::from("tournament"),
+        }
+    }
+}
+
+impl TournamentConfig {
+    pub fn load(path: impl AsRef<Path>) -> error::Result<Self> {
+        let path = path.as_ref();
+        let config: Self = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| ZertzTournamentError::InvalidConfig(path.to_path_buf(), err))?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    fn validate(&self) -> error::Result<()> {
+        if self.players.len() < 2 {
+            return Err(ZertzTournamentError::NotEnoughPlayers(self.players.len()));
+        }
+
+        let mut names = HashSet::new();
+        for player in self.players.iter() {
+            if !names.insert(player.name.as_str()) {
+                return Err(ZertzTournamentError::DuplicatedPlayer(player.name.clone()));
+            }
+            // Without any limit, a search would only end at its maximum depth.
+            if player.depth.is_none() && player.move_time_ms.is_none() && player.nodes.is_none() {
+                return Err(ZertzTournamentError::UnlimitedPlayer(player.name.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pairs of player indices which meet each other.
+    pub fn pairings(&self) -> Vec<(usize, usize)> {
+        let n = self.players.len();
+
+        match self.mode {
+            Mode::RoundRobin => (0..n)
+                .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+                .collect(),
+            Mode::Gauntlet => (1..n).map(|j| (0, j)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(players: usize, mode: Mode) -> TournamentConfig {
+        let players = (0..players)
+            .map(|i| PlayerConfig {
+                name: i.to_string(),
+                depth: Some(1),
+                move_time_ms: None,
+                nodes: None,
+                book: None,
+                hash_mb: 1,
+            })
+            .collect();
+
+        TournamentConfig {
+            players,
+            mode,
+            ..TournamentConfig::default()
+        }
+    }
+
+    #[test]
+    fn pairings() {
+        assert_eq!(
+            config(3, Mode::RoundRobin).pairings(),
+            vec![(0, 1), (0, 2), (1, 2)]
+        );
+        assert_eq!(config(3, Mode::Gauntlet).pairings(), vec![(0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn parse_config() {
+        let json = r#"{
+            "players": [
+                { "name": "shallow", "depth": 2 },
+                { "name": "fast", "move_time_ms": 50, "hash_mb": 4 }
+            ],
+            "mode": "gauntlet",
+            "board_kinds": ["Rings37", "Rings48"],
+            "rules": ["Blitz"]
+        }"#;
+
+        let config: TournamentConfig = serde_json::from_str(json).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.mode, Mode::Gauntlet);
+        assert_eq!(
+            config.board_kinds,
+            vec![BoardKind::Rings37, BoardKind::Rings48]
+        );
+        assert_eq!(config.players[0].hash_mb, DEFAULT_HASH_SIZE_MB);
+        assert_eq!(config.games_per_pair, 2);
+    }
+}
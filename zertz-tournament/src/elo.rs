@@ -0,0 +1,141 @@
+use std::fmt::{self, Display};
+
+// z-score of the 95% confidence interval.
+const CONFIDENCE_Z: f64 = 1.959_964;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Wdl {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Wdl {
+    #[inline]
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Average points per game, where a draw is worth half a point.
+    pub fn score(&self) -> Option<f64> {
+        let games = self.games();
+        (games > 0).then(|| (self.wins as f64 + self.draws as f64 / 2.0) / games as f64)
+    }
+
+    #[inline]
+    pub fn reversed(&self) -> Self {
+        Self {
+            wins: self.losses,
+            draws: self.draws,
+            losses: self.wins,
+        }
+    }
+
+    pub fn add(&mut self, other: Wdl) {
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+    }
+}
+
+impl Display for Wdl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "+{} ={} -{}", self.wins, self.draws, self.losses)
+    }
+}
+
+/// Elo difference with its 95% error bar. Both are infinite if every game was
+/// won or lost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Elo {
+    pub diff: f64,
+    pub error: f64,
+}
+
+impl Elo {
+    pub fn from_wdl(wdl: Wdl) -> Option<Self> {
+        let score = wdl.score()?;
+        let games = wdl.games() as f64;
+
+        let variance = [(wdl.wins, 1.0), (wdl.draws, 0.5), (wdl.losses, 0.0)]
+            .iter()
+            .map(|&(count, points)| count as f64 / games * (points - score).powi(2))
+            .sum::<f64>();
+        let margin = CONFIDENCE_Z * (variance / games).sqrt();
+
+        let diff = score_to_elo(score);
+        let error = (score_to_elo(score + margin) - score_to_elo(score - margin)) / 2.0;
+
+        Some(Self {
+            // Adding zero turns `-0.0` into `0.0`.
+            diff: diff + 0.0,
+            error: if error.is_nan() { f64::INFINITY } else { error },
+        })
+    }
+}
+
+impl Display for Elo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.diff.is_infinite() {
+            let sign = if self.diff > 0.0 { "+" } else { "-" };
+            return write!(f, "{sign}inf");
+        }
+        write!(f, "{:+.1} +/- {:.1}", self.diff, self.error)
+    }
+}
+
+fn score_to_elo(score: f64) -> f64 {
+    if score <= 0.0 {
+        f64::NEG_INFINITY
+    } else if score >= 1.0 {
+        f64::INFINITY
+    } else {
+        -400.0 * (1.0 / score - 1.0).log10()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wdl(wins: u32, draws: u32, losses: u32) -> Wdl {
+        Wdl {
+            wins,
+            draws,
+            losses,
+        }
+    }
+
+    #[test]
+    fn even_score_is_zero_elo() {
+        let elo = Elo::from_wdl(wdl(10, 5, 10)).unwrap();
+        assert!(elo.diff.abs() < 1e-9);
+        assert!(elo.error > 0.0 && elo.error.is_finite());
+    }
+
+    #[test]
+    fn known_elo_difference() {
+        // A score of 75% is about 190.8 Elo.
+        let elo = Elo::from_wdl(wdl(75, 0, 25)).unwrap();
+        assert!((elo.diff - 190.85).abs() < 0.1);
+
+        let reversed = Elo::from_wdl(wdl(75, 0, 25).reversed()).unwrap();
+        assert!((elo.diff + reversed.diff).abs() < 1e-9);
+    }
+
+    #[test]
+    fn error_shrinks_with_more_games() {
+        let few = Elo::from_wdl(wdl(6, 2, 4)).unwrap();
+        let many = Elo::from_wdl(wdl(60, 20, 40)).unwrap();
+        assert!(many.error < few.error);
+    }
+
+    #[test]
+    fn no_games_and_perfect_scores() {
+        assert!(Elo::from_wdl(Wdl::default()).is_none());
+
+        let elo = Elo::from_wdl(wdl(3, 0, 0)).unwrap();
+        assert_eq!(elo.diff, f64::INFINITY);
+        assert_eq!(elo.to_string(), "+inf");
+    }
+}
@@ -0,0 +1,23 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ZertzTournamentError {
+    #[error("{0}")]
+    IOErr(#[from] std::io::Error),
+    #[error("{0}")]
+    ZertzCoreErr(#[from] zertz_core::error::ZertzCoreError),
+    #[error("failed to read the config file {0}.\n Reason: {1}")]
+    InvalidConfig(PathBuf, serde_json::Error),
+    #[error("a tournament needs at least two players. got = {0}")]
+    NotEnoughPlayers(usize),
+    #[error("player {0} has no limit. Give it at least one of depth, move_time_ms and nodes")]
+    UnlimitedPlayer(String),
+    #[error("player names must be unique. got {0} twice")]
+    DuplicatedPlayer(String),
+    #[error("a worker thread is unexpectedly terminated")]
+    WorkerPanicked,
+}
+
+pub type Result<T> = std::result::Result<T, ZertzTournamentError>;
@@ -0,0 +1,56 @@
+mod config;
+mod elo;
+mod error;
+mod report;
+mod runner;
+
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use config::TournamentConfig;
+use report::Report;
+
+/// Play matches between computer players and estimate their strength.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    /// Path of the tournament config in JSON.
+    config: PathBuf,
+    /// Number of games played at the same time. Overrides the config.
+    #[arg(short, long)]
+    threads: Option<usize>,
+    /// Directory where every game record is written. Overrides the config.
+    #[arg(short, long)]
+    output_dir: Option<PathBuf>,
+}
+
+fn main() -> error::Result<()> {
+    let cli = Cli::parse();
+
+    let mut config = TournamentConfig::load(&cli.config)?;
+    if let Some(threads) = cli.threads {
+        config.threads = threads;
+    }
+    if let Some(output_dir) = cli.output_dir {
+        config.output_dir = output_dir;
+    }
+
+    let jobs = runner::schedule(&config);
+    let total = jobs.len();
+    let mut finished = 0;
+
+    let outcomes = runner::run(&config, jobs, |outcome| {
+        finished += 1;
+        let [alice, bob] = &outcome.record.players;
+        println!(
+            "[{finished}/{total}] {alice} vs {bob} on {} ({}): {:?} ({:?})",
+            outcome.job.board_kind, outcome.job.rules, outcome.winner, outcome.reason
+        );
+    })?;
+
+    println!();
+    print!("{}", Report::new(&config, &outcomes).render());
+
+    Ok(())
+}
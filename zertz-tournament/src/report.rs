@@ -0,0 +1,130 @@
+use std::fmt::Write;
+
+use zertz_core::app::EndReason;
+
+use crate::{
+    config::TournamentConfig,
+    elo::{Elo, Wdl},
+    runner::GameOutcome,
+};
+
+/// Results of every pair of players, seen from the first player of the pair.
+pub struct Report {
+    names: Vec<String>,
+    pairs: Vec<((usize, usize), Wdl)>,
+    time_forfeits: usize,
+    adjudicated: usize,
+}
+
+impl Report {
+    pub fn new(config: &TournamentConfig, outcomes: &[GameOutcome]) -> Self {
+        let mut pairs = config
+            .pairings()
+            .into_iter()
+            .map(|pair| (pair, Wdl::default()))
+            .collect::<Vec<_>>();
+
+        for outcome in outcomes {
+            let [alice, bob] = outcome.job.players;
+            let Some((pair, wdl)) = pairs
+                .iter_mut()
+                .find(|((a, b), _)| (*a, *b) == (alice, bob) || (*a, *b) == (bob, alice))
+            else {
+                continue;
+            };
+
+            match outcome.winner_index() {
+                Some(winner) if winner == pair.0 => wdl.wins += 1,
+                Some(_) => wdl.losses += 1,
+                None => wdl.draws += 1,
+            }
+        }
+
+        Self {
+            names: config.players.iter().map(|p| p.name.clone()).collect(),
+            pairs,
+            time_forfeits: count(outcomes, EndReason::Timeout),
+            adjudicated: count(outcomes, EndReason::Adjudication),
+        }
+    }
+
+    /// Results of `player` against everyone else.
+    fn total(&self, player: usize) -> Wdl {
+        let mut output = Wdl::default();
+
+        for &((first, second), wdl) in self.pairs.iter() {
+            if first == player {
+                output.add(wdl);
+            } else if second == player {
+                output.add(wdl.reversed());
+            }
+        }
+
+        output
+    }
+
+    pub fn render(&self) -> String {
+        let pairs = self
+            .pairs
+            .iter()
+            .map(|&((first, second), wdl)| {
+                (
+                    format!("{} - {}", self.names[first], self.names[second]),
+                    wdl,
+                )
+            })
+            .collect::<Vec<_>>();
+        let players = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (name.clone(), self.total(idx)))
+            .collect::<Vec<_>>();
+        let mut output = String::new();
+
+        table(&mut output, "pair", &pairs);
+        output.push('\n');
+        table(&mut output, "player", &players);
+        output.push('\n');
+        output.push_str(&format!(
+            "time forfeits: {}, adjudicated draws: {}\n",
+            self.time_forfeits, self.adjudicated
+        ));
+
+        output
+    }
+}
+
+fn count(outcomes: &[GameOutcome], reason: EndReason) -> usize {
+    outcomes
+        .iter()
+        .filter(|outcome| outcome.reason == reason)
+        .count()
+}
+
+fn table(output: &mut String, title: &str, rows: &[(String, Wdl)]) {
+    let width = rows
+        .iter()
+        .map(|(label, _)| label.len())
+        .chain([title.len()])
+        .max()
+        .unwrap_or_default();
+
+    // `write!` on a `String` never fails.
+    let _ = writeln!(
+        output,
+        "{title:<width$}  {:>16}  {:>6}  elo",
+        "w/d/l", "score"
+    );
+    for (label, wdl) in rows {
+        let score = wdl
+            .score()
+            .map_or("-".to_string(), |score| format!("{:.1}%", score * 100.0));
+        let elo = Elo::from_wdl(*wdl).map_or("-".to_string(), |elo| elo.to_string());
+        let _ = writeln!(
+            output,
+            "{label:<width$}  {:>16}  {score:>6}  {elo}",
+            wdl.to_string()
+        );
+    }
+}
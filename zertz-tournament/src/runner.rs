@@ -0,0 +1,301 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use zertz_core::{
+    app::EndReason,
+    board::BoardKind,
+    book::{BookSelection, OpeningBook},
+    game::{Game, GameState, Player, Rules},
+    record::GameRecord,
+    search::engine::{Engine, SearchLimits},
+};
+
+use crate::{
+    config::{PlayerConfig, TournamentConfig},
+    error::{self, ZertzTournamentError},
+};
+
+// A player with a game clock spends at most this fraction of its remaining
+// time on a single move.
+const MOVES_TO_GO: u32 = 20;
+
+/// A single game to be played, where `players[0]` plays as Alice.
+#[derive(Debug, Clone, Copy)]
+pub struct Job {
+    pub id: usize,
+    pub board_kind: BoardKind,
+    pub rules: Rules,
+    pub players: [usize; 2],
+}
+
+#[derive(Debug, Clone)]
+pub struct GameOutcome {
+    pub job: Job,
+    pub winner: Player,
+    pub reason: EndReason,
+    pub record: GameRecord,
+}
+
+impl GameOutcome {
+    /// Winner as an index of the config players, or `None` for a draw.
+    pub fn winner_index(&self) -> Option<usize> {
+        match self.winner {
+            Player::Alice => Some(self.job.players[0]),
+            Player::Bob => Some(self.job.players[1]),
+            Player::Tie => None,
+        }
+    }
+}
+
+/// Every game of the tournament. Colors alternate between consecutive games
+/// of the same pair, so each player plays Alice equally often.
+pub fn schedule(config: &TournamentConfig) -> Vec<Job> {
+    let mut output = Vec::new();
+
+    for (first, second) in config.pairings() {
+        for &board_kind in config.board_kinds.iter() {
+            for &rules in config.rules.iter() {
+                for round in 0..config.games_per_pair {
+                    let players = if round % 2 == 0 {
+                        [first, second]
+                    } else {
+                        [second, first]
+                    };
+                    output.push(Job {
+                        id: output.len(),
+                        board_kind,
+                        rules,
+                        players,
+                    });
+                }
+            }
+        }
+    }
+
+    output
+}
+
+/// Play every job on `config.threads` threads. `on_game` is called on the
+/// calling thread as soon as each game is over.
+pub fn run(
+    config: &TournamentConfig,
+    jobs: Vec<Job>,
+    mut on_game: impl FnMut(&GameOutcome),
+) -> error::Result<Vec<GameOutcome>> {
+    fs::create_dir_all(&config.output_dir)?;
+
+    let books = load_books(&config.players)?;
+    let jobs = Arc::new(Mutex::new(VecDeque::from(jobs)));
+    let (outcome_sender, outcome_receiver) = channel::<error::Result<GameOutcome>>();
+
+    let workers = (0..config.threads.max(1))
+        .map(|_| {
+            let config = config.clone();
+            let books = books.clone();
+            let jobs = Arc::clone(&jobs);
+            let outcome_sender = outcome_sender.clone();
+            thread::spawn(move || worker(&config, &books, &jobs, outcome_sender))
+        })
+        .collect::<Vec<_>>();
+    drop(outcome_sender);
+
+    let mut output = Vec::new();
+    for outcome in outcome_receiver {
+        let outcome = outcome?;
+        let path = config.output_dir.join(format!(
+            "{:04}-{}-vs-{}.json",
+            outcome.job.id, outcome.record.players[0], outcome.record.players[1]
+        ));
+        fs::write(path, outcome.record.save()?)?;
+
+        on_game(&outcome);
+        output.push(outcome);
+    }
+
+    for worker in workers {
+        worker
+            .join()
+            .map_err(|_| ZertzTournamentError::WorkerPanicked)?;
+    }
+    output.sort_by_key(|outcome| outcome.job.id);
+
+    Ok(output)
+}
+
+fn load_books(players: &[PlayerConfig]) -> error::Result<Vec<Option<OpeningBook>>> {
+    players
+        .iter()
+        .map(|player| match &player.book {
+            Some(path) => Ok(Some(OpeningBook::load(fs::read_to_string(path)?)?)),
+            None => Ok(None),
+        })
+        .collect()
+}
+
+fn worker(
+    config: &TournamentConfig,
+    books: &[Option<OpeningBook>],
+    jobs: &Mutex<VecDeque<Job>>,
+    outcome_sender: Sender<error::Result<GameOutcome>>,
+) {
+    loop {
+        let Some(job) = jobs.lock().expect("job queue is poisoned").pop_front() else {
+            break;
+        };
+
+        let outcome = play_game(config, books, job);
+        if outcome_sender.send(outcome).is_err() {
+            break;
+        }
+    }
+}
+
+fn play_game(
+    config: &TournamentConfig,
+    books: &[Option<OpeningBook>],
+    job: Job,
+) -> error::Result<GameOutcome> {
+    let mut engines = job.players.map(|idx| {
+        let player = &config.players[idx];
+        let mut engine = Engine::new(player.hash_mb);
+        engine.set_book(books[idx].clone(), BookSelection::Weighted);
+        engine.set_seed(job.id as u64);
+        engine
+    });
+    let limits = job.players.map(|idx| config.players[idx].search_limits());
+    let mut clocks = [config.game_time_ms.map(Duration::from_millis); 2];
+
+    let mut game = Game::with_rules(job.board_kind, job.rules);
+    let mut record = GameRecord::new(job.board_kind, job.rules);
+    record.players = job.players.map(|idx| config.players[idx].name.clone());
+
+    let finish = |mut record: GameRecord, winner: Player, reason: EndReason| {
+        record.result = Some(winner);
+        record.reason = Some(reason);
+        Ok(GameOutcome {
+            job,
+            winner,
+            reason,
+            record,
+        })
+    };
+
+    loop {
+        match game.game_state() {
+            GameState::GameEnd(Player::Tie) => {
+                return finish(record, Player::Tie, EndReason::Repetition)
+            }
+            GameState::GameEnd(winner) => return finish(record, winner, EndReason::WinCondition),
+            _ => {}
+        }
+        // A game which goes on too long is adjudicated as a draw.
+        if record.moves.len() >= config.max_plies {
+            return finish(record, Player::Tie, EndReason::Adjudication);
+        }
+
+        let player = game.current_player();
+        let side = usize::from(player);
+        let limits = clock_limits(limits[side], clocks[side]);
+
+        let start = Instant::now();
+        let result = engines[side].search(&game, limits, |_| {});
+
+        if let Some(clock) = clocks[side].as_mut() {
+            match clock.checked_sub(start.elapsed()) {
+                Some(remaining) => *clock = remaining,
+                None => return finish(record, player.opponent(), EndReason::Timeout),
+            }
+        }
+
+        let Some(mv) = result.best_move else {
+            // Nobody has a marble left to put.
+            return finish(record, Player::Tie, EndReason::BoardFilled);
+        };
+        game.play_move(mv)?;
+        record.moves.push(mv);
+    }
+}
+
+// Shorten the move time so that the player does not lose on time.
+fn clock_limits(limits: SearchLimits, clock: Option<Duration>) -> SearchLimits {
+    let Some(clock) = clock else {
+        return limits;
+    };
+
+    let budget = clock / MOVES_TO_GO;
+    SearchLimits {
+        move_time: Some(limits.move_time.map_or(budget, |time| time.min(budget))),
+        ..limits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Mode;
+
+    fn player(name: &str) -> PlayerConfig {
+        PlayerConfig {
+            name: name.to_string(),
+            depth: Some(1),
+            move_time_ms: None,
+            nodes: None,
+            book: None,
+            hash_mb: 1,
+        }
+    }
+
+    #[test]
+    fn colors_alternate() {
+        let config = TournamentConfig {
+            players: vec![player("a"), player("b"), player("c")],
+            mode: Mode::Gauntlet,
+            rules: vec![Rules::Standard, Rules::Blitz],
+            games_per_pair: 2,
+            ..TournamentConfig::default()
+        };
+
+        let jobs = schedule(&config);
+        assert_eq!(jobs.len(), 2 * 2 * 2);
+        assert_eq!(jobs[0].players, [0, 1]);
+        assert_eq!(jobs[1].players, [1, 0]);
+        assert!(jobs.iter().enumerate().all(|(idx, job)| job.id == idx));
+    }
+
+    #[test]
+    fn max_plies_is_a_draw() {
+        let config = TournamentConfig {
+            players: vec![player("a"), player("b")],
+            max_plies: 4,
+            ..TournamentConfig::default()
+        };
+        let job = schedule(&config)[0];
+
+        let outcome = play_game(&config, &[None, None], job).unwrap();
+        assert_eq!(outcome.winner, Player::Tie);
+        assert_eq!(outcome.reason, EndReason::Adjudication);
+        assert_eq!(outcome.record.reason, Some(EndReason::Adjudication));
+        assert_eq!(outcome.record.moves.len(), 4);
+        assert!(outcome.record.replay().is_ok());
+    }
+
+    #[test]
+    fn flag_fall_is_a_timeout() {
+        let config = TournamentConfig {
+            players: vec![player("a"), player("b")],
+            game_time_ms: Some(0),
+            ..TournamentConfig::default()
+        };
+        let job = schedule(&config)[0];
+
+        let outcome = play_game(&config, &[None, None], job).unwrap();
+        assert_ne!(outcome.winner, Player::Tie);
+        assert_eq!(outcome.reason, EndReason::Timeout);
+        assert_eq!(outcome.record.result, Some(outcome.winner));
+        assert_eq!(outcome.record.reason, Some(EndReason::Timeout));
+    }
+}